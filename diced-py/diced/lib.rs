@@ -27,6 +27,12 @@ impl AsRef<str> for Sequence {
     }
 }
 
+impl AsRef<[u8]> for Sequence {
+    fn as_ref(&self) -> &[u8] {
+        self.data.as_bytes()
+    }
+}
+
 impl Clone for Sequence {
     fn clone(&self) -> Self {
         Self {
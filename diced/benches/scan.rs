@@ -0,0 +1,143 @@
+//! Benchmark the scanner and the edit distance on the bundled genomes.
+//!
+//! Run with `cargo bench`, or `cargo bench --features rayon` to include
+//! the parallel scanner; the median time over several iterations is
+//! reported for each genome.
+
+extern crate diced;
+extern crate noodles_fasta;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+extern crate strsim;
+
+use std::time::Duration;
+use std::time::Instant;
+
+const ITERATIONS: usize = 10;
+
+/// Time a function, and report the median time per iteration.
+fn time<F: FnMut() -> usize>(name: &str, mut f: F) {
+    let mut times = (0..ITERATIONS)
+        .map(|_| {
+            let t = Instant::now();
+            std::hint::black_box(f());
+            t.elapsed()
+        })
+        .collect::<Vec<Duration>>();
+    times.sort();
+    let median = times[ITERATIONS / 2];
+    println!("{:<48} median {:>10.3?}", name, median);
+}
+
+fn bench(path: &str) {
+    let mut reader = std::fs::File::open(path)
+        .map(std::io::BufReader::new)
+        .map(noodles_fasta::Reader::new)
+        .unwrap();
+    let record = reader.records().next().unwrap().unwrap();
+    let seq = record.sequence().as_ref();
+    let name = path.rsplit('/').next().unwrap();
+    let builder = diced::ScannerBuilder::new();
+
+    time(&format!("{} (scan)", name), || builder.scan(seq).count());
+
+    let mut indexed = builder.clone();
+    indexed.seeding(diced::Seeding::Index);
+    time(&format!("{} (scan, index)", name), || {
+        indexed.scan(seq).count()
+    });
+
+    #[cfg(feature = "rayon")]
+    {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        time(&format!("{} (par_scan, 1 thread)", name), || {
+            pool.install(|| builder.par_scan(seq).unwrap().len())
+        });
+        time(
+            &format!(
+                "{} (par_scan, {} threads)",
+                name,
+                rayon::current_num_threads()
+            ),
+            || builder.par_scan(seq).unwrap().len(),
+        );
+    }
+
+    let mut circular = builder.clone();
+    circular.circular(true);
+    time(&format!("{} (scan, circular)", name), || {
+        circular.scan(seq).count()
+    });
+
+    // scoring compares every pair of spacers with the edit distance
+    let crisprs = builder.scan(seq).collect::<Vec<_>>();
+    time(&format!("{} (evidence)", name), || {
+        (0..100)
+            .flat_map(|_| crisprs.iter())
+            .map(|c| c.evidence().repeat_count())
+            .sum()
+    });
+}
+
+/// Compare the edit distance algorithms on the repeats and spacers of a genome.
+fn bench_distance(path: &str) {
+    let mut reader = std::fs::File::open(path)
+        .map(std::io::BufReader::new)
+        .map(noodles_fasta::Reader::new)
+        .unwrap();
+    let record = reader.records().next().unwrap().unwrap();
+    let seq = record.sequence().as_ref();
+    let name = path.rsplit('/').next().unwrap();
+
+    // pairs compared by the evidence scoring and the degeneracy report
+    let mut pairs = Vec::new();
+    for crispr in diced::ScannerBuilder::new().scan(seq) {
+        let consensus = crispr.consensus_repeat();
+        for repeat in crispr.repeats() {
+            pairs.push((repeat.to_bytes().to_vec(), consensus.clone()));
+        }
+        let spacers = crispr
+            .spacers()
+            .map(|s| s.to_bytes().to_vec())
+            .collect::<Vec<_>>();
+        for (i, s1) in spacers.iter().enumerate() {
+            for s2 in &spacers[i + 1..] {
+                pairs.push((s1.clone(), s2.clone()));
+            }
+        }
+    }
+    for (s1, s2) in &pairs {
+        let expected = diced::__bench::levenshtein_dp(s1, s2, |x, y| x == y);
+        assert_eq!(diced::__bench::levenshtein(s1, s2), expected);
+        assert_eq!(strsim::generic_levenshtein(s1, s2), expected);
+    }
+
+    time(&format!("{} (levenshtein, dp)", name), || {
+        (0..100)
+            .flat_map(|_| pairs.iter())
+            .map(|(s1, s2)| diced::__bench::levenshtein_dp(s1, s2, |x, y| x == y))
+            .sum()
+    });
+    time(&format!("{} (levenshtein, strsim)", name), || {
+        (0..100)
+            .flat_map(|_| pairs.iter())
+            .map(|(s1, s2)| strsim::generic_levenshtein(s1, s2))
+            .sum()
+    });
+    time(&format!("{} (levenshtein, myers)", name), || {
+        (0..100)
+            .flat_map(|_| pairs.iter())
+            .map(|(s1, s2)| diced::__bench::levenshtein(s1, s2))
+            .sum()
+    });
+}
+
+fn main() {
+    bench("tests/data/Aquifex_aeolicus_VF5.fna");
+    bench("tests/data/NZ_CP019870.1.fna");
+    bench_distance("tests/data/Aquifex_aeolicus_VF5.fna");
+    bench_distance("tests/data/NZ_CP019870.1.fna");
+}
@@ -0,0 +1,96 @@
+/// The policy for ambiguous bases in the scanned sequences.
+///
+/// Ambiguous bases are any symbol other than `A`, `C`, `G`, `T` or `U`,
+/// which includes the `N` used in scaffolding gaps as well as the other
+/// IUPAC ambiguity codes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Ambiguity {
+    /// Treat ambiguous bases as mismatches, like MinCED.
+    #[default]
+    Ignore,
+    /// Never report a CRISPR region across an ambiguous base.
+    ///
+    /// Regions containing an ambiguous base are truncated before it, and
+    /// discarded if too few repeats remain.
+    Break,
+    /// Let IUPAC codes match any compatible base when comparing repeats.
+    ///
+    /// This applies to the approximate repeats found in the flanks of a
+    /// candidate region, while seeds must still be exact matches. An array
+    /// is therefore not found if every copy of every seed window of its
+    /// repeats differs by an ambiguous base.
+    Wildcard,
+    /// Reject sequences containing ambiguous bases.
+    ///
+    /// The fallible entry points, such as [`ScannerBuilder::try_scan`](crate::ScannerBuilder::try_scan),
+    /// report an [`Error::AmbiguousBase`](crate::Error::AmbiguousBase) for
+    /// such sequences, while [`ScannerBuilder::scan`](crate::ScannerBuilder::scan)
+    /// yields no CRISPR region.
+    Reject,
+}
+
+/// Get the set of nucleotides matched by an IUPAC code, as a bitmask.
+#[inline]
+fn iupac_mask(c: u8) -> u8 {
+    match c.to_ascii_uppercase() {
+        b'A' => 0b0001,
+        b'C' => 0b0010,
+        b'G' => 0b0100,
+        b'T' | b'U' => 0b1000,
+        b'M' => 0b0011,
+        b'R' => 0b0101,
+        b'W' => 0b1001,
+        b'S' => 0b0110,
+        b'Y' => 0b1010,
+        b'K' => 0b1100,
+        b'V' => 0b0111,
+        b'H' => 0b1011,
+        b'D' => 0b1101,
+        b'B' => 0b1110,
+        b'N' => 0b1111,
+        _ => 0,
+    }
+}
+
+/// Check whether a symbol is not one of the four unambiguous nucleotides.
+#[inline]
+pub(crate) fn is_ambiguous(c: u8) -> bool {
+    !matches!(
+        c,
+        b'A' | b'C' | b'G' | b'T' | b'U' | b'a' | b'c' | b'g' | b't' | b'u'
+    )
+}
+
+/// Check whether two symbols may stand for the same nucleotide.
+#[inline]
+pub(crate) fn is_compatible(x: u8, y: u8) -> bool {
+    x == y || iupac_mask(x) & iupac_mask(y) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compatible() {
+        assert!(is_compatible(b'A', b'A'));
+        assert!(is_compatible(b'A', b'a'));
+        assert!(is_compatible(b'N', b'G'));
+        assert!(is_compatible(b'R', b'g'));
+        assert!(is_compatible(b'Y', b'S'));
+        assert!(!is_compatible(b'R', b'C'));
+        assert!(!is_compatible(b'A', b'T'));
+        assert!(!is_compatible(b'-', b'N'));
+    }
+
+    #[test]
+    fn ambiguous() {
+        assert!(!is_ambiguous(b'A'));
+        assert!(!is_ambiguous(b'u'));
+        assert!(is_ambiguous(b'N'));
+        assert!(is_ambiguous(b'y'));
+        assert!(is_ambiguous(b'-'));
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+
+use super::Crispr;
+use super::Error;
+use super::ScannerBuilder;
+
+/// The number of sequences queued per worker thread.
+const QUEUE_FACTOR: usize = 4;
+
+type Job<S> = (usize, S);
+type Scanned<S> = std::thread::Result<Result<Vec<Crispr<S>>, Error>>;
+type Outcome<S> = (usize, Scanned<S>);
+
+/// An iterator over the CRISPR regions of many sequences, scanned in parallel.
+///
+/// Created by [`ScannerBuilder::scan_many`]. Sequences are read lazily
+/// from the input iterator, and only a bounded number of them are being
+/// scanned or waiting to be yielded at any given time.
+pub struct ScanMany<I, K, S> {
+    sequences: I,
+    ids: VecDeque<K>,
+    jobs: Option<mpsc::SyncSender<Job<S>>>,
+    outcomes: mpsc::Receiver<Outcome<S>>,
+    buffer: BTreeMap<usize, Scanned<S>>,
+    workers: Vec<JoinHandle<()>>,
+    cancelled: Arc<AtomicBool>,
+    capacity: usize,
+    submitted: usize,
+    yielded: usize,
+}
+
+impl<I, K, S> ScanMany<I, K, S>
+where
+    I: Iterator<Item = (K, S)>,
+    S: AsRef<[u8]> + Clone + Send + 'static,
+{
+    pub(crate) fn new(builder: &ScannerBuilder, sequences: I, threads: usize) -> Self {
+        let threads = threads.max(1);
+        let capacity = threads * QUEUE_FACTOR;
+        let (job_tx, job_rx) = mpsc::sync_channel::<Job<S>>(capacity);
+        let (outcome_tx, outcome_rx) = mpsc::channel::<Outcome<S>>();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let workers = (0..threads)
+            .map(|_| {
+                let builder = builder.clone();
+                let jobs = job_rx.clone();
+                let outcomes = outcome_tx.clone();
+                let cancelled = cancelled.clone();
+                std::thread::spawn(move || loop {
+                    let job = jobs.lock().map(|rx| rx.recv());
+                    let (index, sequence) = match job {
+                        Ok(Ok(job)) => job,
+                        _ => break,
+                    };
+                    if cancelled.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                        builder
+                            .try_scan(sequence)
+                            .map(|scanner| scanner.collect::<Vec<_>>())
+                    }));
+                    if outcomes.send((index, result)).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            sequences,
+            ids: VecDeque::with_capacity(capacity),
+            jobs: Some(job_tx),
+            outcomes: outcome_rx,
+            buffer: BTreeMap::new(),
+            workers,
+            cancelled,
+            capacity,
+            submitted: 0,
+            yielded: 0,
+        }
+    }
+
+    /// Submit new sequences until the maximum number of pending results is reached.
+    fn fill(&mut self) {
+        while self.submitted - self.yielded < self.capacity {
+            let (id, sequence) = match self.sequences.next() {
+                Some(item) => item,
+                None => break,
+            };
+            let jobs = self.jobs.as_ref().expect("job queue is open");
+            if jobs.send((self.submitted, sequence)).is_err() {
+                panic!("all worker threads have stopped");
+            }
+            self.ids.push_back(id);
+            self.submitted += 1;
+        }
+    }
+}
+
+impl<I, K, S> Iterator for ScanMany<I, K, S>
+where
+    I: Iterator<Item = (K, S)>,
+    S: AsRef<[u8]> + Clone + Send + 'static,
+{
+    type Item = (K, Result<Vec<Crispr<S>>, Error>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.fill();
+        let id = self.ids.pop_front()?;
+        let result = loop {
+            if let Some(result) = self.buffer.remove(&self.yielded) {
+                break result;
+            }
+            match self.outcomes.recv() {
+                Ok((index, result)) => {
+                    self.buffer.insert(index, result);
+                }
+                Err(_) => panic!("all worker threads have stopped"),
+            }
+        };
+        self.yielded += 1;
+        match result {
+            Ok(scanned) => Some((id, scanned)),
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl<I, K, S> Drop for ScanMany<I, K, S> {
+    fn drop(&mut self) {
+        // the job queue still delivers the queued jobs once closed, so the
+        // workers must also be told to skip them and stop after their
+        // current job
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.jobs.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use super::distance;
+use super::Crispr;
+use super::Strand;
+
+/// The divergence of a single repeat from the consensus repeat.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Degeneracy {
+    index: usize,
+    mismatches: Vec<usize>,
+    distance: usize,
+    trailer: bool,
+}
+
+impl Degeneracy {
+    /// Get the index of the repeat in the CRISPR region.
+    ///
+    /// Repeats are indexed in the order of the scanned sequence, so that
+    /// this index can be passed to [`Crispr::repeat`].
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the positions where the repeat differs from the consensus.
+    ///
+    /// Positions are given in the consensus repeat, from an alignment of
+    /// the repeat to the consensus, so that an indel only shifts a single
+    /// position. Bases inserted in the repeat are reported at the position
+    /// of the consensus they precede, which may be the consensus length.
+    #[inline]
+    pub fn mismatches(&self) -> &[usize] {
+        self.mismatches.as_slice()
+    }
+
+    /// Get the edit distance between the repeat and the consensus.
+    #[inline]
+    pub fn distance(&self) -> usize {
+        self.distance
+    }
+
+    /// Check whether the repeat is a degenerate trailer repeat.
+    ///
+    /// Only the terminal repeat at the trailer end of the array can be
+    /// flagged, if it diverges from the consensus more than any other
+    /// repeat. When the strand is unknown, both terminal repeats are
+    /// candidates, and the most divergent one is flagged.
+    #[inline]
+    pub fn is_degenerate_trailer(&self) -> bool {
+        self.trailer
+    }
+
+    /// Check whether the repeat differs from the consensus.
+    #[inline]
+    pub fn is_degenerate(&self) -> bool {
+        self.distance > 0
+    }
+}
+
+/// Compute the degeneracy of every repeat of a CRISPR region.
+pub(crate) fn degeneracies<S: AsRef<[u8]>>(crispr: &Crispr<S>) -> Vec<Degeneracy> {
+    let consensus = crispr.consensus_repeat();
+
+    let mut degeneracies = crispr
+        .indices
+        .iter()
+        .zip(&crispr.ends)
+        .enumerate()
+        .map(|(index, (&start, &end))| {
+            let repeat = crispr.slice(start, end).to_ascii_uppercase();
+            let (distance, mismatches) = distance::levenshtein_alignment(&repeat, &consensus);
+            Degeneracy {
+                index,
+                mismatches,
+                distance,
+                trailer: false,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if degeneracies.len() >= 2 {
+        let last = degeneracies.len() - 1;
+        let trailer = match crispr.strand {
+            Strand::Plus => Some(last),
+            Strand::Minus => Some(0),
+            Strand::Unknown => match degeneracies[0].distance.cmp(&degeneracies[last].distance) {
+                std::cmp::Ordering::Less => Some(last),
+                std::cmp::Ordering::Greater => Some(0),
+                std::cmp::Ordering::Equal => None,
+            },
+        };
+        if let Some(t) = trailer {
+            let distance = degeneracies[t].distance;
+            if distance > 0
+                && degeneracies
+                    .iter()
+                    .filter(|d| d.index != t)
+                    .all(|d| d.distance < distance)
+            {
+                degeneracies[t].trailer = true;
+            }
+        }
+    }
+
+    degeneracies
+}
@@ -0,0 +1,361 @@
+//! Distance metrics between raw byte sequences.
+
+/// Compute the Levenshtein distance between two byte sequences.
+pub fn levenshtein(s1: &[u8], s2: &[u8]) -> usize {
+    levenshtein_by(s1, s2, |x, y| x == y)
+}
+
+/// Compute the Levenshtein distance with a custom symbol equality.
+///
+/// Sequences of up to 128 symbols are compared with the bit-parallel
+/// algorithm of Myers, without allocating; longer ones fall back to the
+/// dynamic programming algorithm. `eq` must be symmetric, since the
+/// shortest sequence is used as the pattern.
+pub fn levenshtein_by<F: Fn(u8, u8) -> bool>(s1: &[u8], s2: &[u8], eq: F) -> usize {
+    let (pattern, text) = if s1.len() <= s2.len() {
+        (s1, s2)
+    } else {
+        (s2, s1)
+    };
+    if pattern.len() <= 64 {
+        myers64(pattern, text, eq)
+    } else if pattern.len() <= 128 {
+        myers128(pattern, text, eq)
+    } else {
+        levenshtein_dp(s1, s2, eq)
+    }
+}
+
+macro_rules! myers {
+    ($name:ident, $word:ty) => {
+        /// Compute the Levenshtein distance with the algorithm of Myers.
+        ///
+        /// The pattern must not be longer than the number of bits in a word.
+        fn $name<F: Fn(u8, u8) -> bool>(pattern: &[u8], text: &[u8], eq: F) -> usize {
+            let m = pattern.len();
+            if m == 0 {
+                return text.len();
+            }
+
+            // match masks of the pattern, computed for the symbols of the text
+            let mut peq = [0 as $word; 256];
+            let mut known = [false; 256];
+            let last = 1 << (m - 1);
+
+            let mut pv = !0;
+            let mut mv = 0;
+            let mut score = m;
+            for &y in text {
+                if !known[y as usize] {
+                    known[y as usize] = true;
+                    peq[y as usize] = pattern
+                        .iter()
+                        .enumerate()
+                        .filter(|&(_, &x)| eq(x, y))
+                        .fold(0, |mask, (i, _)| mask | (1 << i));
+                }
+                let eq = peq[y as usize];
+                let xv = eq | mv;
+                let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+                let mut ph = mv | !(xh | pv);
+                let mut mh = pv & xh;
+                if ph & last != 0 {
+                    score += 1;
+                } else if mh & last != 0 {
+                    score -= 1;
+                }
+                ph = (ph << 1) | 1;
+                mh <<= 1;
+                pv = mh | !(xv | ph);
+                mv = ph & xv;
+            }
+            score
+        }
+    };
+}
+
+myers!(myers64, u64);
+myers!(myers128, u128);
+
+/// Compute the Levenshtein distance with dynamic programming.
+///
+/// This is the reference for the bit-parallel implementation, exposed to
+/// the benchmarks through [`crate::__bench`].
+pub fn levenshtein_dp<F: Fn(u8, u8) -> bool>(s1: &[u8], s2: &[u8], eq: F) -> usize {
+    let mut cache = (1..s2.len() + 1).collect::<Vec<usize>>();
+    let mut result = s2.len();
+
+    for (i, &x) in s1.iter().enumerate() {
+        result = i + 1;
+        let mut distance = i;
+        for (j, &y) in s2.iter().enumerate() {
+            let cost = usize::from(!eq(x, y));
+            let d = distance + cost;
+            distance = cache[j];
+            result = (result + 1).min(distance + 1).min(d);
+            cache[j] = result;
+        }
+    }
+
+    result
+}
+
+/// Align two byte sequences and get their edits in the second sequence.
+///
+/// Returns the Levenshtein distance between the sequences, and the
+/// positions of `s2` involved in an edit of an optimal alignment: the
+/// substituted or deleted positions, and the position before which
+/// bases of `s1` are inserted, which may be `s2.len()`.
+pub fn levenshtein_alignment(s1: &[u8], s2: &[u8]) -> (usize, Vec<usize>) {
+    let width = s2.len() + 1;
+    let mut matrix = vec![0; (s1.len() + 1) * width];
+    for (j, cell) in matrix[..width].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for (i, &x) in s1.iter().enumerate() {
+        matrix[(i + 1) * width] = i + 1;
+        for (j, &y) in s2.iter().enumerate() {
+            let diagonal = matrix[i * width + j] + usize::from(x != y);
+            let up = matrix[i * width + j + 1] + 1;
+            let left = matrix[(i + 1) * width + j] + 1;
+            matrix[(i + 1) * width + j + 1] = diagonal.min(up).min(left);
+        }
+    }
+
+    // trace back an optimal alignment, preferring matches and substitutions
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (s1.len(), s2.len());
+    while i > 0 || j > 0 {
+        let d = matrix[i * width + j];
+        if i > 0
+            && j > 0
+            && d == matrix[(i - 1) * width + j - 1] + usize::from(s1[i - 1] != s2[j - 1])
+        {
+            if s1[i - 1] != s2[j - 1] {
+                edits.push(j - 1);
+            }
+            i -= 1;
+            j -= 1;
+        } else if j > 0 && d == matrix[i * width + j - 1] + 1 {
+            edits.push(j - 1);
+            j -= 1;
+        } else {
+            edits.push(j);
+            i -= 1;
+        }
+    }
+    edits.reverse();
+    edits.dedup();
+
+    (matrix[matrix.len() - 1], edits)
+}
+
+/// Compute the Hamming distance between two byte sequences.
+///
+/// If the sequences have different lengths, the length difference is
+/// added to the Hamming distance of the shortest prefix. This is only
+/// used as a reference for [`best_hamming_window`].
+#[cfg(test)]
+pub fn hamming(s1: &[u8], s2: &[u8]) -> usize {
+    hamming_by(s1, s2, |x, y| x == y)
+}
+
+/// Compute the Hamming distance with a custom symbol equality.
+#[cfg(test)]
+pub fn hamming_by<F: Fn(u8, u8) -> bool>(s1: &[u8], s2: &[u8], eq: F) -> usize {
+    let l = s1.len().min(s2.len());
+    let d = s1.len().abs_diff(s2.len());
+    s1[..l]
+        .iter()
+        .zip(&s2[..l])
+        .filter(|(&x, &y)| !eq(x, y))
+        .count()
+        + d
+}
+
+/// The number of symbols compared between two checks of the distance bound.
+const HAMMING_CHUNK: usize = 16;
+
+/// Compute the Hamming distance of two equally-sized sequences, up to a bound.
+///
+/// Symbols are compared in fixed-size chunks so that the comparison can
+/// be vectorized, and the comparison stops as soon as the distance
+/// reaches `bound`, in which case a distance of at least `bound` is returned.
+#[inline]
+fn hamming_bounded<F: Fn(u8, u8) -> bool>(s1: &[u8], s2: &[u8], eq: &F, bound: usize) -> usize {
+    let mut d = 0;
+    let mut c1 = s1.chunks(HAMMING_CHUNK);
+    let mut c2 = s2.chunks(HAMMING_CHUNK);
+    while let (Some(x), Some(y)) = (c1.next(), c2.next()) {
+        d += x
+            .iter()
+            .zip(y)
+            .map(|(&a, &b)| usize::from(!eq(a, b)))
+            .sum::<usize>();
+        if d >= bound {
+            break;
+        }
+    }
+    d
+}
+
+/// Find the window of `text` with the lowest Hamming distance to `pattern`.
+///
+/// Returns the offset of the first best window with its distance, or
+/// `None` if `text` is shorter than `pattern`. With the `memchr` feature,
+/// exact occurrences of `pattern` are searched first.
+pub fn best_hamming_window(pattern: &[u8], text: &[u8]) -> Option<(usize, usize)> {
+    #[cfg(feature = "memchr")]
+    if let Some(i) = memchr::memmem::find(text, pattern) {
+        return Some((i, 0));
+    }
+    best_hamming_window_by(pattern, text, |x, y| x == y)
+}
+
+/// Find the window of `text` with the lowest Hamming distance, with a custom symbol equality.
+pub fn best_hamming_window_by<F: Fn(u8, u8) -> bool>(
+    pattern: &[u8],
+    text: &[u8],
+    eq: F,
+) -> Option<(usize, usize)> {
+    if text.len() < pattern.len() {
+        return None;
+    }
+    if pattern.is_empty() {
+        return Some((0, 0));
+    }
+    let mut best = (0, usize::MAX);
+    for (i, window) in text.windows(pattern.len()).enumerate() {
+        let d = hamming_bounded(pattern, window, &eq, best.1);
+        if d < best.1 {
+            best = (i, d);
+            if d == 0 {
+                break;
+            }
+        }
+    }
+    Some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levenshtein_strsim() {
+        let pairs: &[(&str, &str)] = &[
+            ("", ""),
+            ("", "ACGT"),
+            ("kitten", "sitting"),
+            (
+                "GTTTTAACTCCACACGGTACATTAGAAAC",
+                "GTTTCAACTCCACACGGTACATTAGGAAC",
+            ),
+            ("ACGTACGT", "TGCA"),
+        ];
+        for (s1, s2) in pairs {
+            assert_eq!(
+                levenshtein(s1.as_bytes(), s2.as_bytes()),
+                strsim::levenshtein(s1, s2)
+            );
+            assert_eq!(
+                levenshtein(s2.as_bytes(), s1.as_bytes()),
+                strsim::levenshtein(s2, s1)
+            );
+        }
+    }
+
+    #[test]
+    fn levenshtein_myers() {
+        // compare against dynamic programming on pseudo-random sequences
+        let mut state = 1u32;
+        let mut random = |n: usize| {
+            (0..n)
+                .map(|_| {
+                    state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                    b"ACGTn"[(state >> 28) as usize % 5]
+                })
+                .collect::<Vec<u8>>()
+        };
+        let eq = |x: u8, y: u8| x.eq_ignore_ascii_case(&y);
+        for (n, m) in [
+            (0, 5),
+            (1, 1),
+            (30, 35),
+            (64, 64),
+            (65, 60),
+            (100, 128),
+            (130, 140),
+        ] {
+            for _ in 0..10 {
+                let s1 = random(n);
+                let s2 = random(m);
+                let expected = levenshtein_dp(&s1, &s2, |x, y| x == y);
+                assert_eq!(levenshtein(&s1, &s2), expected);
+                assert_eq!(levenshtein(&s2, &s1), expected);
+                assert_eq!(levenshtein_by(&s1, &s2, eq), levenshtein_dp(&s1, &s2, eq));
+            }
+        }
+    }
+
+    #[test]
+    fn alignment() {
+        assert_eq!(levenshtein_alignment(b"ACGTACGT", b"ACGTACGT"), (0, vec![]));
+        assert_eq!(
+            levenshtein_alignment(b"ACGAACGT", b"ACGTACGT"),
+            (1, vec![3])
+        );
+        assert_eq!(levenshtein_alignment(b"ACGACGT", b"ACGTACGT"), (1, vec![3]));
+        assert_eq!(
+            levenshtein_alignment(b"ACGTTACGT", b"ACGTACGT"),
+            (1, vec![3])
+        );
+        assert_eq!(
+            levenshtein_alignment(b"ACGTACGTA", b"ACGTACGT"),
+            (1, vec![8])
+        );
+        assert_eq!(levenshtein_alignment(b"", b"ACG"), (3, vec![0, 1, 2]));
+        for (s1, s2) in [(&b"GATTACA"[..], &b"GCATGCT"[..]), (b"TTAGG", b"AGGCT")] {
+            assert_eq!(levenshtein_alignment(s1, s2).0, levenshtein(s1, s2));
+        }
+    }
+
+    #[test]
+    fn best_window() {
+        let text = b"TTACGATTACCTTACGTTT";
+        assert_eq!(best_hamming_window(b"ACGT", text), Some((13, 0)));
+        assert_eq!(best_hamming_window(b"ACCA", text), Some((2, 1)));
+        assert_eq!(best_hamming_window(b"GGGG", b"AAAA"), Some((0, 4)));
+        assert_eq!(best_hamming_window(b"ACGT", b"ACG"), None);
+        assert_eq!(best_hamming_window(b"", b"ACG"), Some((0, 0)));
+        let eq = |x: u8, y: u8| x.eq_ignore_ascii_case(&y);
+        assert_eq!(best_hamming_window_by(b"acgt", text, eq), Some((13, 0)));
+
+        // compare against an exhaustive search on pseudo-random sequences
+        let mut state = 7u32;
+        let mut random = |n: usize| {
+            (0..n)
+                .map(|_| {
+                    state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                    b"ACGT"[(state >> 30) as usize]
+                })
+                .collect::<Vec<u8>>()
+        };
+        for _ in 0..50 {
+            let pattern = random(35);
+            let text = random(100);
+            let expected = (0..=text.len() - pattern.len())
+                .map(|i| (i, hamming(&pattern, &text[i..i + pattern.len()])))
+                .min_by_key(|&(_, d)| d);
+            assert_eq!(best_hamming_window(&pattern, &text), expected);
+        }
+    }
+
+    #[test]
+    fn hamming_unequal() {
+        assert_eq!(hamming(b"ACGT", b"ACGT"), 0);
+        assert_eq!(hamming(b"ACGT", b"ACCT"), 1);
+        assert_eq!(hamming(b"ACGT", b"AC"), 2);
+        assert_eq!(hamming(b"", b"ACG"), 3);
+    }
+}
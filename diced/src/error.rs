@@ -0,0 +1,146 @@
+use std::error::Error as StdError;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+/// An error caused by invalid [`ScannerBuilder`](crate::ScannerBuilder) parameters.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ParameterError {
+    /// The minimum repeat count is lower than two.
+    MinRepeatCount(usize),
+    /// The minimum repeat length is greater than the maximum repeat length.
+    RepeatLength { min: usize, max: usize },
+    /// The minimum spacer length is greater than the maximum spacer length.
+    SpacerLength { min: usize, max: usize },
+    /// The search window is empty or longer than the minimum repeat length.
+    SearchWindowLength(usize),
+    /// The repeat threshold is not a fraction.
+    RepeatThreshold(f32),
+    /// The maximum spacer similarity is not a fraction.
+    SpacerToSpacerMaxSimilarity(f32),
+    /// The flank confidence is not a fraction.
+    FlankConfidence(f32),
+    /// The right scan range is longer than the maximum repeat spacing.
+    RightScanRange(usize),
+    /// The flank scan range is longer than the maximum repeat spacing.
+    FlankScanRange(usize),
+    /// The maximum number of indels is not lower than the minimum repeat length.
+    MaxRepeatIndels(usize),
+    /// The homopolymer mask size is zero.
+    MaskSize(usize),
+    /// The maximum array span is shorter than a repeat and a spacer.
+    MaxArraySpan(usize),
+}
+
+impl Display for ParameterError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::MinRepeatCount(n) => {
+                write!(f, "invalid min_repeat_count: expected at least 2, got {}", n)
+            }
+            Self::RepeatLength { min, max } => write!(
+                f,
+                "invalid repeat length: min_repeat_length ({}) is greater than max_repeat_length ({})",
+                min, max
+            ),
+            Self::SpacerLength { min, max } => write!(
+                f,
+                "invalid spacer length: min_spacer_length ({}) is greater than max_spacer_length ({})",
+                min, max
+            ),
+            Self::SearchWindowLength(n) => write!(
+                f,
+                "invalid search_window_length: expected a value between 1 and min_repeat_length, got {}",
+                n
+            ),
+            Self::RepeatThreshold(x) => {
+                write!(f, "invalid repeat_threshold: expected a fraction, got {}", x)
+            }
+            Self::SpacerToSpacerMaxSimilarity(x) => write!(
+                f,
+                "invalid spacer_to_spacer_max_similarity: expected a fraction, got {}",
+                x
+            ),
+            Self::FlankConfidence(x) => {
+                write!(f, "invalid flank_confidence: expected a fraction, got {}", x)
+            }
+            Self::RightScanRange(n) => write!(
+                f,
+                "invalid right_scan_range: expected at most max_repeat_length + max_spacer_length, got {}",
+                n
+            ),
+            Self::FlankScanRange(n) => write!(
+                f,
+                "invalid flank_scan_range: expected at most max_repeat_length + max_spacer_length, got {}",
+                n
+            ),
+            Self::MaxRepeatIndels(n) => write!(
+                f,
+                "invalid max_repeat_indels: expected a value lower than min_repeat_length, got {}",
+                n
+            ),
+            Self::MaskSize(n) => {
+                write!(f, "invalid mask_size: expected at least 1, got {}", n)
+            }
+            Self::MaxArraySpan(n) => write!(
+                f,
+                "invalid max_array_span: expected at least max_repeat_length + max_spacer_length + search_window_length, got {}",
+                n
+            ),
+        }
+    }
+}
+
+impl StdError for ParameterError {}
+
+/// An error raised when scanning a sequence.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The scanner parameters are invalid.
+    Parameter(ParameterError),
+    /// The sequence contains an ambiguous base, and ambiguous bases are rejected.
+    AmbiguousBase { position: usize, base: u8 },
+    /// The scanned range ends before it starts.
+    ScanRange { start: usize, end: usize },
+    /// Circular mode was requested for a sequence read from a reader.
+    CircularStream,
+}
+
+impl From<ParameterError> for Error {
+    fn from(error: ParameterError) -> Self {
+        Self::Parameter(error)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Parameter(e) => e.fmt(f),
+            Self::AmbiguousBase { position, base } => write!(
+                f,
+                "ambiguous base {:?} at position {}",
+                char::from(*base),
+                position
+            ),
+            Self::ScanRange { start, end } => write!(
+                f,
+                "invalid scan range: start ({}) is greater than end ({})",
+                start, end
+            ),
+            Self::CircularStream => {
+                f.write_str("circular mode is not supported when scanning a reader")
+            }
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Parameter(e) => Some(e),
+            Self::AmbiguousBase { .. } | Self::ScanRange { .. } | Self::CircularStream => None,
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use super::distance;
+use super::Crispr;
+
+/// The minimum number of spacers for a CRISPR region above evidence level 1.
+const MIN_SPACERS: usize = 4;
+/// The minimum repeat conservation for evidence level 3.
+const LEVEL3_CONSERVATION: f32 = 0.8;
+/// The minimum repeat conservation for evidence level 4.
+const LEVEL4_CONSERVATION: f32 = 0.9;
+/// The minimum spacer diversity for evidence levels 3 and 4.
+const MIN_DIVERSITY: f32 = 0.3;
+/// The minimum spacer length regularity for evidence level 4.
+const MIN_REGULARITY: f32 = 0.75;
+
+/// The evidence supporting a CRISPR region.
+///
+/// The evidence combines the features checked by the scanner into a
+/// score between 0 and 1, and an evidence level between 1 and 4 in the
+/// spirit of CRISPRCasFinder: level 1 marks small arrays, level 2 arrays
+/// with poorly conserved repeats or similar spacers, which are often
+/// tandem repeats, and levels 3 and 4 increasingly confident arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evidence {
+    repeat_count: usize,
+    repeat_conservation: f32,
+    spacer_diversity: f32,
+    spacer_regularity: f32,
+}
+
+impl Evidence {
+    /// Get the number of repeats in the CRISPR region.
+    #[inline]
+    pub fn repeat_count(&self) -> usize {
+        self.repeat_count
+    }
+
+    /// Get the conservation of the repeats, between 0 and 1.
+    ///
+    /// This is the frequency of the consensus nucleotide, averaged over
+    /// all the positions of the repeat.
+    #[inline]
+    pub fn repeat_conservation(&self) -> f32 {
+        self.repeat_conservation
+    }
+
+    /// Get the diversity of the spacers, between 0 and 1.
+    ///
+    /// This is one minus the highest similarity between two spacers of
+    /// the region, so that a single duplicated spacer lowers the diversity.
+    /// Unrelated spacers of similar lengths are usually about half similar,
+    /// so the diversity of genuine CRISPR arrays rarely exceeds 0.5.
+    #[inline]
+    pub fn spacer_diversity(&self) -> f32 {
+        self.spacer_diversity
+    }
+
+    /// Get the regularity of the spacer lengths, between 0 and 1.
+    ///
+    /// This is one minus the coefficient of variation of the spacer
+    /// lengths, clamped at zero.
+    #[inline]
+    pub fn spacer_regularity(&self) -> f32 {
+        self.spacer_regularity
+    }
+
+    /// Get the score of the CRISPR region, between 0 and 1.
+    ///
+    /// The score is the mean of the repeat conservation, the spacer
+    /// diversity and the spacer regularity, weighted by a factor growing
+    /// with the number of repeats.
+    pub fn score(&self) -> f32 {
+        let size = 1.0 - 1.0 / self.repeat_count.max(1) as f32;
+        let mean =
+            (self.repeat_conservation + self.spacer_diversity + self.spacer_regularity) / 3.0;
+        mean * size
+    }
+
+    /// Get the evidence level of the CRISPR region, between 1 and 4.
+    pub fn level(&self) -> u8 {
+        if self.repeat_count.saturating_sub(1) < MIN_SPACERS {
+            1
+        } else if self.repeat_conservation >= LEVEL4_CONSERVATION
+            && self.spacer_diversity >= MIN_DIVERSITY
+            && self.spacer_regularity >= MIN_REGULARITY
+        {
+            4
+        } else if self.repeat_conservation >= LEVEL3_CONSERVATION
+            && self.spacer_diversity >= MIN_DIVERSITY
+        {
+            3
+        } else {
+            2
+        }
+    }
+}
+
+/// Compute the evidence supporting a CRISPR region.
+pub(crate) fn evidence<S: AsRef<[u8]>>(crispr: &Crispr<S>) -> Evidence {
+    let profile = crispr.profile();
+    let counted = profile.iter().filter(|count| count.total() > 0);
+    let (sum, n) = counted.fold((0.0, 0), |(sum, n), count| {
+        (sum + count.max() as f32 / count.total() as f32, n + 1)
+    });
+    let repeat_conservation = if n > 0 { sum / n as f32 } else { 0.0 };
+
+    let spacers = (1..crispr.len())
+        .map(|k| {
+            crispr
+                .slice(crispr.ends[k - 1], crispr.indices[k])
+                .to_ascii_uppercase()
+        })
+        .collect::<Vec<_>>();
+
+    let mut max_similarity = 0.0f32;
+    for (i, s1) in spacers.iter().enumerate() {
+        for s2 in &spacers[i + 1..] {
+            let max_len = s1.len().max(s2.len());
+            if max_len > 0 {
+                let d = distance::levenshtein(s1, s2);
+                max_similarity = max_similarity.max(1.0 - d as f32 / max_len as f32);
+            }
+        }
+    }
+
+    let spacer_regularity = if spacers.is_empty() {
+        0.0
+    } else {
+        let n = spacers.len() as f32;
+        let mean = spacers.iter().map(|s| s.len() as f32).sum::<f32>() / n;
+        let variance = spacers
+            .iter()
+            .map(|s| (s.len() as f32 - mean).powi(2))
+            .sum::<f32>()
+            / n;
+        if mean > 0.0 {
+            (1.0 - variance.sqrt() / mean).max(0.0)
+        } else {
+            0.0
+        }
+    };
+
+    Evidence {
+        repeat_count: crispr.len(),
+        repeat_conservation,
+        spacer_diversity: if spacers.is_empty() {
+            0.0
+        } else {
+            1.0 - max_similarity
+        },
+        spacer_regularity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level() {
+        let mut evidence = Evidence {
+            repeat_count: 3,
+            repeat_conservation: 1.0,
+            spacer_diversity: 1.0,
+            spacer_regularity: 1.0,
+        };
+        assert_eq!(evidence.level(), 1);
+        evidence.repeat_count = 5;
+        assert_eq!(evidence.level(), 4);
+        assert_eq!(evidence.score(), 0.8);
+        evidence.spacer_regularity = 0.5;
+        assert_eq!(evidence.level(), 3);
+        evidence.spacer_diversity = 0.1;
+        assert_eq!(evidence.level(), 2);
+    }
+}
@@ -0,0 +1,127 @@
+//! BED6 and BED12 output, for genome browsers and `bedtools`.
+
+use std::io::Error as IoError;
+use std::io::Write;
+use std::ops::Range;
+
+use super::repeat_starts;
+use crate::Crispr;
+
+/// A writer for CRISPR regions in BED6 or BED12 format.
+#[derive(Debug)]
+pub struct BedWriter<W: Write> {
+    writer: W,
+    id_prefix: String,
+    bed12: bool,
+    count: usize,
+}
+
+impl<W: Write> BedWriter<W> {
+    /// Create a new writer with default settings.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            id_prefix: String::from("CRISPR"),
+            bed12: false,
+            count: 0,
+        }
+    }
+
+    /// Set whether to write BED12 lines with one block per repeat.
+    ///
+    /// Defaults to `false`, which writes BED6 lines.
+    pub fn bed12(&mut self, bed12: bool) -> &mut Self {
+        self.bed12 = bed12;
+        self
+    }
+
+    /// Write a CRISPR region found in the sequence named `seqid`.
+    ///
+    /// The score is the number of repeats, capped at 1000, and the strand
+    /// is the predicted [`Strand`](crate::Strand) of the region.
+    pub fn write<S: AsRef<[u8]> + Clone>(
+        &mut self,
+        seqid: &str,
+        crispr: &Crispr<S>,
+    ) -> Result<(), IoError> {
+        self.count += 1;
+
+        let name = format!("{}{}", self.id_prefix, self.count);
+        let region = crispr.to_region();
+        let start = region.start();
+        let end = start + region.len();
+        let blocks = repeat_starts(crispr)
+            .into_iter()
+            .zip(crispr.repeats())
+            .map(|(start, repeat)| (start, start + repeat.len()))
+            .collect::<Vec<_>>();
+
+        if region.is_wrapped() {
+            let length = end - region.end();
+            self.write_record(seqid, &name, crispr, start..length, &blocks, 0)?;
+            self.write_record(seqid, &name, crispr, length..end, &blocks, length)
+        } else {
+            self.write_record(seqid, &name, crispr, start..end, &blocks, 0)
+        }
+    }
+
+    /// Write a single line for the repeats overlapping `range`.
+    ///
+    /// `range` and `blocks` are unwrapped coordinates, from which `shift`
+    /// is subtracted to get coordinates in the sequence.
+    fn write_record<S: AsRef<[u8]> + Clone>(
+        &mut self,
+        seqid: &str,
+        name: &str,
+        crispr: &Crispr<S>,
+        range: Range<usize>,
+        blocks: &[(usize, usize)],
+        shift: usize,
+    ) -> Result<(), IoError> {
+        let blocks = blocks
+            .iter()
+            .map(|&(start, end)| (start.max(range.start), end.min(range.end)))
+            .filter(|(start, end)| start < end)
+            .collect::<Vec<_>>();
+        let (start, end) = if self.bed12 {
+            (blocks[0].0, blocks[blocks.len() - 1].1)
+        } else {
+            (range.start, range.end)
+        };
+
+        write!(
+            self.writer,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            seqid,
+            start - shift,
+            end - shift,
+            name,
+            crispr.len().min(1000),
+            crispr.strand().as_char(),
+        )?;
+
+        if self.bed12 {
+            let sizes = blocks
+                .iter()
+                .map(|(block_start, block_end)| (block_end - block_start).to_string())
+                .collect::<Vec<_>>();
+            let starts = blocks
+                .iter()
+                .map(|(block_start, _)| (block_start - start).to_string())
+                .collect::<Vec<_>>();
+            write!(
+                self.writer,
+                "\t{}\t{}\t0\t{}\t{}\t{}",
+                start - shift,
+                end - shift,
+                blocks.len(),
+                sizes.join(","),
+                starts.join(","),
+            )?;
+        }
+
+        writeln!(self.writer)
+    }
+}
+
+writer_methods!(BedWriter, id_prefix);
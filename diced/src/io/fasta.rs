@@ -0,0 +1,81 @@
+//! FASTA output of spacers, compatible with the `-spacers` output of MinCED.
+
+use std::io::Error as IoError;
+use std::io::Write;
+
+use crate::Crispr;
+use crate::Region;
+
+/// A writer for the spacers of CRISPR regions in FASTA format.
+#[derive(Debug)]
+pub struct FastaWriter<W: Write> {
+    writer: W,
+    repeats: bool,
+    count: usize,
+}
+
+impl<W: Write> FastaWriter<W> {
+    /// Create a new writer with default settings.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            repeats: false,
+            count: 0,
+        }
+    }
+
+    /// Set whether to write a record for every repeat.
+    ///
+    /// Repeats are not written by MinCED, so this is disabled by default.
+    pub fn repeats(&mut self, repeats: bool) -> &mut Self {
+        self.repeats = repeats;
+        self
+    }
+
+    /// Write the spacers of a CRISPR region found in the sequence named `seqid`.
+    ///
+    /// Records are named such as `NC_000918.1_CRISPR_1_spacer_2`, with
+    /// one-based, inclusive coordinates such as `NC_000918.1:156556-156599`
+    /// as their description.
+    pub fn write<S: AsRef<[u8]> + Clone>(
+        &mut self,
+        seqid: &str,
+        crispr: &Crispr<S>,
+    ) -> Result<(), IoError> {
+        self.count += 1;
+        for i in 0..crispr.len() {
+            if self.repeats {
+                self.write_record(seqid, "repeat", i + 1, &crispr.repeat(i))?;
+            }
+            if i + 1 < crispr.len() {
+                self.write_record(seqid, "spacer", i + 1, &crispr.spacer(i))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a single repeat or spacer record.
+    fn write_record<S: AsRef<[u8]> + Clone>(
+        &mut self,
+        seqid: &str,
+        kind: &str,
+        index: usize,
+        region: &Region<S>,
+    ) -> Result<(), IoError> {
+        writeln!(
+            self.writer,
+            ">{}_CRISPR_{}_{}_{} {}:{}-{}",
+            seqid,
+            self.count,
+            kind,
+            index,
+            seqid,
+            region.start() + 1,
+            region.start() + region.len(),
+        )?;
+        self.writer.write_all(&region.to_bytes())?;
+        writeln!(self.writer)
+    }
+}
+
+writer_methods!(FastaWriter);
@@ -0,0 +1,134 @@
+//! GFF3 output, compatible with the `-gff` output of MinCED.
+
+use std::io::Error as IoError;
+use std::io::Write;
+
+use super::repeat_starts;
+use crate::Crispr;
+
+/// A writer for CRISPR regions in GFF3 format, like the `-gff` output of MinCED.
+#[derive(Debug)]
+pub struct GffWriter<W: Write> {
+    writer: W,
+    source: String,
+    id_prefix: String,
+    repeat_prefix: String,
+    spacer_prefix: String,
+    spacers: bool,
+    count: usize,
+}
+
+impl<W: Write> GffWriter<W> {
+    /// Create a new writer with default settings.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            source: String::from("minced:0.4.2"),
+            id_prefix: String::from("CRISPR"),
+            repeat_prefix: String::from("DR"),
+            spacer_prefix: String::from("SP"),
+            spacers: false,
+            count: 0,
+        }
+    }
+
+    /// Set the source column of the features.
+    ///
+    /// Defaults to `minced:0.4.2` for compatibility with MinCED.
+    pub fn source<S: Into<String>>(&mut self, source: S) -> &mut Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Set the prefix of the repeat identifiers.
+    ///
+    /// Defaults to `DR`, which gives identifiers such as `DR.CRISPR1.1`.
+    pub fn repeat_prefix<S: Into<String>>(&mut self, repeat_prefix: S) -> &mut Self {
+        self.repeat_prefix = repeat_prefix.into();
+        self
+    }
+
+    /// Set the prefix of the spacer identifiers.
+    ///
+    /// Defaults to `SP`, which gives identifiers such as `SP.CRISPR1.1`.
+    pub fn spacer_prefix<S: Into<String>>(&mut self, spacer_prefix: S) -> &mut Self {
+        self.spacer_prefix = spacer_prefix.into();
+        self
+    }
+
+    /// Set whether to write a `spacer` feature for every spacer.
+    ///
+    /// Spacers are not written by MinCED, so this is disabled by default.
+    pub fn spacers(&mut self, spacers: bool) -> &mut Self {
+        self.spacers = spacers;
+        self
+    }
+
+    /// Write a CRISPR region found in the sequence named `seqid`.
+    ///
+    /// The region is written as a `repeat_region` feature, followed by its
+    /// `repeat_unit` features, and by its `spacer` features if enabled.
+    pub fn write<S: AsRef<[u8]> + Clone>(
+        &mut self,
+        seqid: &str,
+        crispr: &Crispr<S>,
+    ) -> Result<(), IoError> {
+        if self.count == 0 {
+            writeln!(self.writer, "##gff-version 3")?;
+        }
+        self.count += 1;
+
+        let id = format!("{}{}", self.id_prefix, self.count);
+        let region = crispr.to_region();
+        let unit = crispr.repeat(1.min(crispr.len() - 1));
+        let starts = repeat_starts(crispr);
+        writeln!(
+            self.writer,
+            "{}\t{}\trepeat_region\t{}\t{}\t{}\t.\t.\tID={};rpt_type=direct;rpt_family=CRISPR;rpt_unit_seq={}",
+            seqid,
+            self.source,
+            region.start() + 1,
+            region.start() + region.len(),
+            crispr.len(),
+            id,
+            String::from_utf8_lossy(&unit.to_bytes()),
+        )?;
+
+        for (i, (start, repeat)) in starts.iter().zip(crispr.repeats()).enumerate() {
+            writeln!(
+                self.writer,
+                "{}\t{}\trepeat_unit\t{}\t{}\t1\t.\t.\tParent={};ID={}.{}.{}",
+                seqid,
+                self.source,
+                start + 1,
+                start + repeat.len(),
+                id,
+                self.repeat_prefix,
+                id,
+                i + 1,
+            )?;
+        }
+
+        if self.spacers {
+            for (i, (start, spacer)) in starts.iter().zip(crispr.spacers()).enumerate() {
+                let start = start + crispr.repeat(i).len();
+                writeln!(
+                    self.writer,
+                    "{}\t{}\tspacer\t{}\t{}\t.\t.\t.\tParent={};ID={}.{}.{}",
+                    seqid,
+                    self.source,
+                    start + 1,
+                    start + spacer.len(),
+                    id,
+                    self.spacer_prefix,
+                    id,
+                    i + 1,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+writer_methods!(GffWriter, id_prefix);
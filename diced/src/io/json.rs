@@ -0,0 +1,159 @@
+//! JSON Lines output, with an owned representation of CRISPR regions.
+//!
+//! Each line written by a [`JsonWriter`] is a JSON object describing a
+//! single CRISPR region, which can be deserialized back into a
+//! [`CrisprRecord`]. The schema of each object is the following:
+//!
+//! | Field               | Type              | Description                                              |
+//! |---------------------|-------------------|----------------------------------------------------------|
+//! | `seqid`             | string            | The name of the sequence containing the region.          |
+//! | `id`                | string            | The identifier of the region, such as `CRISPR1`.         |
+//! | `start`             | integer           | The start of the region (zero-based, inclusive).         |
+//! | `end`               | integer           | The end of the region (zero-based, exclusive).           |
+//! | `strand`            | string            | The predicted strand: `plus`, `minus` or `unknown`.      |
+//! | `strand_confidence` | number            | The confidence of the strand prediction, from 0 to 1.    |
+//! | `consensus_repeat`  | string            | The consensus sequence of the repeats.                   |
+//! | `repeats`           | array of elements | The repeats of the region, in sequence order.            |
+//! | `spacers`           | array of elements | The spacers of the region, in sequence order.            |
+//!
+//! Each repeat and spacer element is an object with the following fields:
+//!
+//! | Field      | Type    | Description                                       |
+//! |------------|---------|---------------------------------------------------|
+//! | `start`    | integer | The start of the element (zero-based, inclusive). |
+//! | `end`      | integer | The end of the element (zero-based, exclusive).   |
+//! | `sequence` | string  | The sequence of the element.                      |
+//!
+//! New fields may be added to the schema in future versions, but the
+//! existing fields will not be renamed or removed.
+//!
+//! The parameters of a [`ScannerBuilder`](crate::ScannerBuilder) can be
+//! serialized as well, as a JSON object with one field per parameter.
+//!
+//! ```
+//! let sequence = std::fs::read_to_string("tests/data/Aquifex_aeolicus_VF5.fna")
+//!     .unwrap()
+//!     .lines()
+//!     .skip(1)
+//!     .collect::<String>();
+//! let mut writer = diced::io::json::JsonWriter::new(Vec::new());
+//! for crispr in diced::ScannerBuilder::new().scan(sequence.as_str()) {
+//!     writer.write("NC_000918.1", &crispr).unwrap();
+//! }
+//!
+//! let output = String::from_utf8(writer.into_inner()).unwrap();
+//! let first = output.lines().next().unwrap();
+//! let record: diced::io::json::CrisprRecord = serde_json::from_str(first).unwrap();
+//! assert_eq!(record.id, "CRISPR1");
+//! assert_eq!(record.start, 156459);
+//! ```
+
+use std::io::Error as IoError;
+use std::io::Write;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Crispr;
+use crate::Region;
+use crate::Strand;
+
+/// An owned repeat or spacer of a CRISPR region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElementRecord {
+    /// The start of the element (zero-based, inclusive).
+    pub start: usize,
+    /// The end of the element (zero-based, exclusive).
+    pub end: usize,
+    /// The sequence of the element.
+    pub sequence: String,
+}
+
+impl ElementRecord {
+    /// Create a new record from a repeat or spacer region.
+    pub fn new<S: AsRef<[u8]> + Clone>(region: &Region<S>) -> Self {
+        Self {
+            start: region.start(),
+            end: region.end(),
+            sequence: String::from_utf8_lossy(&region.to_bytes()).into_owned(),
+        }
+    }
+}
+
+/// An owned CRISPR region, independent of the scanned sequence.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrisprRecord {
+    /// The name of the sequence containing the region.
+    pub seqid: String,
+    /// The identifier of the region.
+    pub id: String,
+    /// The start of the region (zero-based, inclusive).
+    pub start: usize,
+    /// The end of the region (zero-based, exclusive).
+    pub end: usize,
+    /// The predicted strand of the region.
+    pub strand: Strand,
+    /// The confidence of the strand prediction, between 0 and 1.
+    pub strand_confidence: f32,
+    /// The consensus sequence of the repeats.
+    pub consensus_repeat: String,
+    /// The repeats of the region, in sequence order.
+    pub repeats: Vec<ElementRecord>,
+    /// The spacers of the region, in sequence order.
+    pub spacers: Vec<ElementRecord>,
+}
+
+impl CrisprRecord {
+    /// Create a new record for a CRISPR region found in the sequence `seqid`.
+    pub fn new<S: AsRef<[u8]> + Clone>(seqid: &str, id: &str, crispr: &Crispr<S>) -> Self {
+        Self {
+            seqid: seqid.to_string(),
+            id: id.to_string(),
+            start: crispr.start(),
+            end: crispr.end(),
+            strand: crispr.strand(),
+            strand_confidence: crispr.strand_confidence(),
+            consensus_repeat: String::from_utf8_lossy(&crispr.consensus_repeat()).into_owned(),
+            repeats: crispr.repeats().map(|r| ElementRecord::new(&r)).collect(),
+            spacers: crispr.spacers().map(|s| ElementRecord::new(&s)).collect(),
+        }
+    }
+}
+
+/// A writer for CRISPR regions in JSON Lines format, one [`CrisprRecord`] per line.
+#[derive(Debug)]
+pub struct JsonWriter<W: Write> {
+    writer: W,
+    id_prefix: String,
+    count: usize,
+}
+
+impl<W: Write> JsonWriter<W> {
+    /// Create a new writer with default settings.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            id_prefix: String::from("CRISPR"),
+            count: 0,
+        }
+    }
+
+    /// Write a CRISPR region found in the sequence named `seqid`.
+    pub fn write<S: AsRef<[u8]> + Clone>(
+        &mut self,
+        seqid: &str,
+        crispr: &Crispr<S>,
+    ) -> Result<(), IoError> {
+        self.count += 1;
+        let id = format!("{}{}", self.id_prefix, self.count);
+        self.write_record(&CrisprRecord::new(seqid, &id, crispr))
+    }
+
+    /// Write an owned CRISPR region.
+    pub fn write_record(&mut self, record: &CrisprRecord) -> Result<(), IoError> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        writeln!(self.writer)
+    }
+}
+
+writer_methods!(JsonWriter, id_prefix);
@@ -0,0 +1,232 @@
+//! Writers for CRISPR regions in common file formats.
+//!
+//! Each writer wraps a [`std::io::Write`] implementation, which can be
+//! flushed with `flush` and recovered with `into_inner`.
+//!
+//! # Numbering
+//!
+//! CRISPR regions are numbered from 1 in the order they are written,
+//! across all the sequences, like MinCED, so that their identifiers are
+//! unique in a file. Writers for formats with identifiers name regions
+//! such as `CRISPR1`, with a prefix that can be changed with `id_prefix`.
+//!
+//! # Circular sequences
+//!
+//! Regions crossing the origin of a circular sequence are written with
+//! coordinates continuing past the end of the sequence, following the
+//! GFF3 convention for circular landmarks, so that the repeats and spacers
+//! after the origin stay within their region. BED features cannot end past
+//! the end of their chromosome, so [`bed::BedWriter`] splits these regions
+//! at the origin into two lines with the same name instead. JSON records
+//! use the coordinates of the Rust API, where the end of these regions is
+//! lower than their start.
+
+/// Implement the methods shared by all the writers.
+macro_rules! writer_methods {
+    ($name:ident) => {
+        impl<W: std::io::Write> $name<W> {
+            /// Flush the underlying writer.
+            pub fn flush(&mut self) -> Result<(), std::io::Error> {
+                self.writer.flush()
+            }
+
+            /// Get the underlying writer.
+            pub fn into_inner(self) -> W {
+                self.writer
+            }
+        }
+    };
+    ($name:ident, id_prefix) => {
+        writer_methods!($name);
+
+        impl<W: std::io::Write> $name<W> {
+            /// Set the prefix of the CRISPR region identifiers.
+            ///
+            /// Defaults to `CRISPR`, which gives identifiers such as `CRISPR1`.
+            pub fn id_prefix<S: Into<String>>(&mut self, id_prefix: S) -> &mut Self {
+                self.id_prefix = id_prefix.into();
+                self
+            }
+        }
+    };
+}
+
+pub mod bed;
+pub mod fasta;
+pub mod gff;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod report;
+
+use crate::Crispr;
+
+/// Get the start of every repeat of a CRISPR region, without wrapping.
+///
+/// Repeats and spacers are contiguous, so repeats are positioned from the
+/// start of the region: repeats after the origin of a circular sequence
+/// start past the end of the sequence, and stay within the region.
+fn repeat_starts<S: AsRef<[u8]> + Clone>(crispr: &Crispr<S>) -> Vec<usize> {
+    let mut start = crispr.start();
+    let mut starts = Vec::with_capacity(crispr.len());
+    for (i, repeat) in crispr.repeats().enumerate() {
+        starts.push(start);
+        start += repeat.len();
+        if i + 1 < crispr.len() {
+            start += crispr.spacer(i).len();
+        }
+    }
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScannerBuilder;
+
+    /// Scan a CRISPR region, then the same region crossing the origin.
+    fn circular() -> (usize, Crispr<Vec<u8>>, Crispr<Vec<u8>>) {
+        const PAD: &str = concat!(
+            "GCTAAAGACAATTACATAACATACACGTCAGCACGAAACTTGTTGGCCCAGTGTGAATCG",
+            "CTTAAGGGTTAAGTAAGTGTGATGCATACGCCTTTACTTGCTGTGTCCACCCCATCGGAC",
+        );
+        let seq = [crate::tests::SEQ, PAD].concat().into_bytes();
+        let rotated = [&seq[150..], &seq[..150]].concat();
+        let shift = seq.len() - 150;
+        let crispr = ScannerBuilder::default().scan(seq).next().unwrap();
+        let wrapped = ScannerBuilder::default()
+            .circular(true)
+            .scan(rotated)
+            .next()
+            .unwrap();
+        assert!(wrapped.is_wrapped());
+        (shift, crispr, wrapped)
+    }
+
+    /// Get the start and end columns of GFF3 lines with the given type.
+    fn gff_columns(gff: &str, ty: &str) -> Vec<(usize, usize)> {
+        gff.lines()
+            .map(|line| line.split('\t').collect::<Vec<_>>())
+            .filter(|fields| fields.len() == 9 && fields[2] == ty)
+            .map(|fields| (fields[3].parse().unwrap(), fields[4].parse().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn repeat_starts_circular() {
+        let (shift, crispr, wrapped) = circular();
+        let starts = repeat_starts(&wrapped);
+        assert_eq!(starts.len(), crispr.len());
+        for (start, repeat) in starts.iter().zip(crispr.repeats()) {
+            assert_eq!(*start, repeat.start() + shift);
+        }
+        assert_eq!(repeat_starts(&crispr)[0], crispr.start());
+    }
+
+    #[test]
+    fn gff_circular() {
+        let (shift, crispr, wrapped) = circular();
+        let mut writer = gff::GffWriter::new(Vec::new());
+        writer.spacers(true);
+        writer.write("seq", &crispr).unwrap();
+        let linear = String::from_utf8(writer.into_inner()).unwrap();
+        let mut writer = gff::GffWriter::new(Vec::new());
+        writer.spacers(true);
+        writer.write("seq", &wrapped).unwrap();
+        let circular = String::from_utf8(writer.into_inner()).unwrap();
+
+        let length = crate::tests::SEQ.len() + 120;
+        let region = gff_columns(&circular, "repeat_region");
+        assert_eq!(region.len(), 1);
+        let (region_start, region_end) = region[0];
+        assert!(region_start <= length && region_end > length);
+        for ty in ["repeat_region", "repeat_unit", "spacer"] {
+            let expected = gff_columns(&linear, ty)
+                .into_iter()
+                .map(|(start, end)| (start + shift, end + shift))
+                .collect::<Vec<_>>();
+            let actual = gff_columns(&circular, ty);
+            assert_eq!(actual, expected);
+            for (start, end) in actual {
+                assert!(region_start <= start && end <= region_end);
+            }
+        }
+    }
+
+    #[test]
+    fn report_circular() {
+        let (shift, crispr, wrapped) = circular();
+        let mut writer = report::ReportWriter::new(Vec::new());
+        let elapsed = std::time::Duration::from_millis(0);
+        writer.write("seq", 450, &[wrapped], elapsed).unwrap();
+        let report = String::from_utf8(writer.into_inner()).unwrap();
+
+        let range = format!(
+            "CRISPR 1   Range: {} - {}",
+            crispr.start() + shift + 1,
+            crispr.start() + shift + crispr.to_region().len()
+        );
+        assert!(report.contains(&range));
+        let positions = report
+            .lines()
+            .filter_map(|line| line.split("\t\t").next()?.parse::<usize>().ok())
+            .collect::<Vec<_>>();
+        let expected = crispr
+            .repeats()
+            .map(|repeat| repeat.start() + shift + 1)
+            .collect::<Vec<_>>();
+        assert_eq!(positions, expected);
+    }
+
+    #[test]
+    fn bed_circular() {
+        let (shift, crispr, wrapped) = circular();
+        let length = crate::tests::SEQ.len() + 120;
+
+        let mut writer = bed::BedWriter::new(Vec::new());
+        writer.write("seq", &wrapped).unwrap();
+        let bed = String::from_utf8(writer.into_inner()).unwrap();
+        let lines = bed.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(&format!("seq\t{}\t{}\tCRISPR1\t", wrapped.start(), length)));
+        assert!(lines[1].starts_with(&format!("seq\t0\t{}\tCRISPR1\t", wrapped.end())));
+
+        let mut writer = bed::BedWriter::new(Vec::new());
+        writer.bed12(true);
+        writer.write("seq", &wrapped).unwrap();
+        let bed = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(bed.lines().count(), 2);
+        let mut blocks = Vec::new();
+        for (line, offset) in bed.lines().zip([0, length]) {
+            let fields = line.split('\t').collect::<Vec<_>>();
+            assert_eq!(fields.len(), 12);
+            assert_eq!(fields[3], "CRISPR1");
+            let start = fields[1].parse::<usize>().unwrap();
+            let end = fields[2].parse::<usize>().unwrap();
+            assert!(start < end && end <= length);
+            let count = fields[9].parse::<usize>().unwrap();
+            let sizes = fields[10].split(',').map(|x| x.parse::<usize>().unwrap());
+            let starts = fields[11].split(',').map(|x| x.parse::<usize>().unwrap());
+            let part = starts
+                .zip(sizes)
+                .map(|(x, size)| (offset + start + x, offset + start + x + size))
+                .collect::<Vec<_>>();
+            assert_eq!(part.len(), count);
+            assert_eq!(part[0].0, offset + start);
+            assert_eq!(part[count - 1].1, offset + end);
+            blocks.extend(part);
+        }
+
+        // merge the blocks split at the origin to recover the repeats
+        blocks.dedup_by(|next, previous| {
+            next.0 == previous.1 && {
+                previous.1 = next.1;
+                true
+            }
+        });
+        let expected = crispr
+            .repeats()
+            .map(|repeat| (repeat.start() + shift, repeat.end() + shift))
+            .collect::<Vec<_>>();
+        assert_eq!(blocks, expected);
+    }
+}
@@ -0,0 +1,119 @@
+//! Text report, in the layout of the default output of MinCED.
+
+use std::io::Error as IoError;
+use std::io::Write;
+use std::time::Duration;
+
+use super::repeat_starts;
+use crate::Crispr;
+
+/// A writer for CRISPR regions in the default text report of MinCED.
+#[derive(Debug)]
+pub struct ReportWriter<W: Write> {
+    writer: W,
+    count: usize,
+}
+
+impl<W: Write> ReportWriter<W> {
+    /// Create a new writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer, count: 0 }
+    }
+
+    /// Write the CRISPR regions found in the sequence named `seqid`.
+    ///
+    /// `length` is the length of the whole sequence, and `elapsed` the
+    /// time taken to scan it, which MinCED reports after the last region.
+    /// Like MinCED, nothing is written for a sequence without any CRISPR
+    /// region.
+    pub fn write<S: AsRef<[u8]> + Clone>(
+        &mut self,
+        seqid: &str,
+        length: usize,
+        crisprs: &[Crispr<S>],
+        elapsed: Duration,
+    ) -> Result<(), IoError> {
+        if crisprs.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(self.writer, "Sequence '{}' ({} bp)", seqid, length)?;
+        writeln!(self.writer)?;
+        for crispr in crisprs {
+            self.count += 1;
+            self.write_crispr(self.count, crispr)?;
+        }
+        writeln!(
+            self.writer,
+            "Time to find repeats: {} ms",
+            elapsed.as_millis()
+        )?;
+        writeln!(self.writer)?;
+        writeln!(self.writer)?;
+
+        Ok(())
+    }
+
+    /// Write the block of a single CRISPR region.
+    fn write_crispr<S: AsRef<[u8]> + Clone>(
+        &mut self,
+        number: usize,
+        crispr: &Crispr<S>,
+    ) -> Result<(), IoError> {
+        let repeat_total = crispr.repeats().map(|r| r.len()).sum::<usize>();
+        let spacer_total = crispr.spacers().map(|s| s.len()).sum::<usize>();
+        let repeat_average = repeat_total / crispr.len().max(1);
+        let spacer_average = spacer_total / crispr.spacers().len().max(1);
+        let rule = format!(
+            "--------\t{}\t{}",
+            "-".repeat(crispr.repeat(0).len()),
+            "-".repeat(spacer_average),
+        );
+
+        writeln!(
+            self.writer,
+            "CRISPR {}   Range: {} - {}",
+            number,
+            crispr.start() + 1,
+            crispr.start() + crispr.to_region().len(),
+        )?;
+        writeln!(self.writer, "POSITION\tREPEAT\t\t\t\tSPACER")?;
+        writeln!(self.writer, "{}", rule)?;
+        for (i, (start, repeat)) in repeat_starts(crispr)
+            .iter()
+            .zip(crispr.repeats())
+            .enumerate()
+        {
+            write!(
+                self.writer,
+                "{}\t\t{}\t",
+                start + 1,
+                String::from_utf8_lossy(&repeat.to_bytes()),
+            )?;
+            if i + 1 < crispr.len() {
+                let spacer = crispr.spacer(i);
+                write!(
+                    self.writer,
+                    "{}\t[ {}, {} ]",
+                    String::from_utf8_lossy(&spacer.to_bytes()),
+                    repeat.len(),
+                    spacer.len(),
+                )?;
+            }
+            writeln!(self.writer)?;
+        }
+        writeln!(self.writer, "{}", rule)?;
+        writeln!(
+            self.writer,
+            "Repeats: {}\tAverage Length: {}\t\tAverage Length: {}",
+            crispr.len(),
+            repeat_average,
+            spacer_average,
+        )?;
+        writeln!(self.writer)?;
+
+        Ok(())
+    }
+}
+
+writer_methods!(ReportWriter);
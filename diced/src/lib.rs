@@ -2,57 +2,98 @@
 
 #[cfg(feature = "memchr")]
 extern crate memchr;
-
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+mod ambiguity;
+mod batch;
+mod degeneracy;
+mod distance;
+mod error;
+mod evidence;
+pub mod io;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod profile;
 mod region;
-
+mod seeding;
+mod significance;
+mod strand;
+mod stream;
+
+pub use self::ambiguity::Ambiguity;
+pub use self::batch::ScanMany;
+pub use self::degeneracy::Degeneracy;
+pub use self::error::Error;
+pub use self::error::ParameterError;
+pub use self::evidence::Evidence;
+pub use self::profile::DnaCount;
+pub use self::profile::Profile;
 pub use self::region::Region;
 pub use self::region::Regions;
+pub use self::seeding::Seeding;
+pub use self::significance::Significance;
+pub use self::strand::Strand;
+pub use self::stream::StreamScanner;
+
+/// Edit distance implementations, only exposed for the benchmarks.
+#[doc(hidden)]
+pub mod __bench {
+    pub use super::distance::levenshtein;
+    pub use super::distance::levenshtein_dp;
+}
 
 use self::region::RegionType;
+use self::seeding::KmerIndex;
+use std::borrow::Cow;
+use std::io::BufRead;
 use std::ops::Deref;
+use std::ops::Range;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Flank {
-    Left,
-    Right,
-}
-
-#[derive(Debug, Default)]
-struct DnaCount {
-    a: usize,
-    t: usize,
-    c: usize,
-    g: usize,
-}
-
-impl DnaCount {
-    fn new() -> Self {
-        Self::default()
+/// Find the first occurrence of `needle` in `haystack`.
+#[cfg(not(feature = "memchr"))]
+fn memmem(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
     }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
 
-    #[inline]
-    fn clear(&mut self) {
-        self.a = 0;
-        self.c = 0;
-        self.t = 0;
-        self.g = 0;
+/// Find the first occurrence of `needle` in `haystack`, ignoring ASCII case.
+fn memmem_ignore_case(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    let first = match needle.first() {
+        Some(&c) => c,
+        None => return Some(0),
+    };
+    if haystack.len() < needle.len() {
+        return None;
     }
+    let candidates = &haystack[..=haystack.len() - needle.len()];
 
-    #[inline]
-    fn count(&mut self, c: char) {
-        match c {
-            'a' | 'A' => self.a += 1,
-            'c' | 'C' => self.c += 1,
-            'g' | 'G' => self.g += 1,
-            't' | 'T' => self.t += 1,
-            _ => (),
-        }
-    }
+    #[cfg(feature = "memchr")]
+    let mut positions = memchr::memchr2_iter(
+        first.to_ascii_lowercase(),
+        first.to_ascii_uppercase(),
+        candidates,
+    );
+    #[cfg(not(feature = "memchr"))]
+    let mut positions = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.eq_ignore_ascii_case(&first))
+        .map(|(i, _)| i);
+
+    positions.find(|&i| haystack[i..i + needle.len()].eq_ignore_ascii_case(needle))
+}
 
-    #[inline]
-    fn max(&self) -> usize {
-        self.a.max(self.c).max(self.t).max(self.g)
-    }
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Flank {
+    Left,
+    Right,
 }
 
 #[derive(Debug)]
@@ -61,30 +102,102 @@ struct Sequence<S> {
     mask: Vec<Region<S>>,
 }
 
-impl<S: AsRef<str> + Clone> Sequence<S> {
-    const MASK_SIZE: usize = 100;
-    pub fn new(sequence: S) -> Self {
-        let s = sequence.as_ref().as_bytes();
+/// Find the tandem repeats of a given period longer than `mask_size`.
+///
+/// A tandem repeat is a region where every symbol is equal to the symbol
+/// `period` positions after it, such as a microsatellite. Its positions
+/// matching the next period span more than `mask_size - period` bases,
+/// so sampling every `mask_size - period + 1` positions finds all of them.
+fn tandem_repeats(s: &[u8], period: usize, mask_size: usize) -> Vec<(usize, usize)> {
+    let mut repeats = Vec::new();
+    if s.len() <= period {
+        return repeats;
+    }
+    let matches = |k: usize| s[k] == s[k + period];
+    let last = s.len() - period;
+    let step = (mask_size + 1).saturating_sub(period).max(1);
+    let mut q = 0;
+    let mut covered = 0;
+    while q < last {
+        if q >= covered && matches(q) {
+            let i = q - (0..q).rev().take_while(|&k| matches(k)).count();
+            let j = q + 1 + (q + 1..last).take_while(|&k| matches(k)).count();
+            if j + period - i > mask_size {
+                repeats.push((i, j + period));
+            }
+            covered = j;
+        }
+        q += step;
+    }
+    repeats
+}
+
+impl<S: AsRef<[u8]> + Clone> Sequence<S> {
+    pub fn new(sequence: S, mask_size: usize, mask_period: usize) -> Self {
+        let s = sequence.as_ref();
         let mut mask = Vec::new();
 
-        let mut i = 0;
-        let mut j;
-        let mut n;
+        if mask_size < 2 {
+            let mut i = 0;
+            let mut j;
+            let mut n;
 
-        while i < s.len() {
-            n = 0;
-            j = i + 1;
+            while i < s.len() {
+                n = 0;
+                j = i + 1;
 
-            while j < s.len() && s[i] == s[j] {
-                n += 1;
-                j += 1;
-            }
+                while j < s.len() && s[i] == s[j] {
+                    n += 1;
+                    j += 1;
+                }
+
+                if n >= mask_size {
+                    mask.push(Region::new(sequence.clone(), i, j));
+                }
 
-            if n >= Self::MASK_SIZE {
-                mask.push(Region::new(sequence.clone(), i, j));
+                i = j;
+            }
+        } else {
+            // a masked run is longer than `mask_size`, so it contains at
+            // least one position sampled every `mask_size` positions
+            let mut p = 0;
+            let mut covered = 0;
+            while p < s.len() {
+                if p >= covered {
+                    let c = s[p];
+                    let i = p - s[..p].iter().rev().take_while(|&&x| x == c).count();
+                    let j = p + 1 + s[p + 1..].iter().take_while(|&&x| x == c).count();
+                    if j - i > mask_size {
+                        mask.push(Region::new(sequence.clone(), i, j));
+                    }
+                    covered = j;
+                }
+                p += mask_size;
             }
+        }
 
-            i = j;
+        if mask_period > 1 {
+            let mut intervals = mask
+                .iter()
+                .map(|r| (r.start(), r.end()))
+                .chain((2..=mask_period).flat_map(|p| tandem_repeats(s, p, mask_size)))
+                .collect::<Vec<_>>();
+            intervals.sort_unstable();
+            mask.clear();
+            let mut merged: Option<(usize, usize)> = None;
+            for (i, j) in intervals {
+                merged = match merged {
+                    Some((start, end)) if i < end => Some((start, end.max(j))),
+                    Some((start, end)) => {
+                        mask.push(Region::new(sequence.clone(), start, end));
+                        Some((i, j))
+                    }
+                    None => Some((i, j)),
+                };
+            }
+            if let Some((start, end)) = merged {
+                mask.push(Region::new(sequence.clone(), start, end));
+            }
         }
 
         // add an empty mask at the end to facilitate some scanning code later
@@ -113,7 +226,12 @@ impl<S> Deref for Sequence<S> {
 }
 
 /// A builder type to parameterize a [`Scanner`].
+///
+/// With the `serde` feature, the parameters can be serialized and
+/// deserialized, and missing parameters take their default value.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct ScannerBuilder {
     min_repeat_count: usize,
     min_repeat_length: usize,
@@ -121,6 +239,25 @@ pub struct ScannerBuilder {
     min_spacer_length: usize,
     max_spacer_length: usize,
     search_window_length: usize,
+    repeat_threshold: f32,
+    spacer_to_spacer_max_similarity: f32,
+    spacer_to_spacer_length_diff: usize,
+    spacer_to_repeat_length_diff: usize,
+    right_scan_range: usize,
+    flank_scan_range: usize,
+    flank_confidence: f32,
+    max_repeat_indels: usize,
+    mask_size: usize,
+    mask_period: usize,
+    circular: bool,
+    ambiguity: Ambiguity,
+    ignore_case: bool,
+    normalize_case: bool,
+    threads: usize,
+    max_array_span: usize,
+    null_shuffles: usize,
+    null_seed: u64,
+    seeding: Seeding,
 }
 
 impl ScannerBuilder {
@@ -131,18 +268,262 @@ impl ScannerBuilder {
 
     /// Scan the provided sequence for CRISPR regions iteratively.
     ///
-    /// The sequence can be provided as any string view that also implements
+    /// The sequence can be provided as any byte view that also implements
     /// [`Clone`]. This allows several smart pointers to be passed (`&str`,
-    /// `Rc<str>`, `Arc<str>`, etc.). The actual `sequence` object however
-    /// will be cloned into the result [`Crispr`], so make sure it implements
-    /// a cheap [`Clone`], and avoid passing a [`String`].
+    /// `&[u8]`, `Rc<[u8]>`, `Arc<[u8]>`, `bytes::Bytes`, etc.). The actual
+    /// `sequence` object however will be cloned into the result [`Crispr`],
+    /// so make sure it implements a cheap [`Clone`], and avoid passing a
+    /// [`String`] or a [`Vec<u8>`].
     ///
-    pub fn scan<S: AsRef<str> + Clone>(&self, sequence: S) -> Scanner<S> {
-        let mut scanner = Scanner::new(sequence);
-        self.clone_into(&mut scanner.parameters);
+    /// The parameters are not validated, and inconsistent parameters will
+    /// simply yield no CRISPR region. Likewise, a sequence containing an
+    /// ambiguous base with the [`Ambiguity::Reject`] policy yields no
+    /// CRISPR region. Use [`ScannerBuilder::try_scan`] to get an error in
+    /// both cases instead.
+    pub fn scan<S: AsRef<[u8]> + Clone>(&self, sequence: S) -> Scanner<S> {
+        let rejected = self.check_sequence(sequence.as_ref()).is_err();
+        let mut scanner = Scanner::with_parameters(sequence, self.clone());
+        if rejected {
+            scanner.search_limit = 0;
+            scanner.origin_probed = true;
+        }
         scanner
     }
 
+    /// Build a scanner for the provided sequence after validating the parameters.
+    ///
+    /// This is an alias for [`ScannerBuilder::try_scan`].
+    ///
+    /// # Errors
+    /// Returns an error if the parameters are invalid, or if the sequence
+    /// is rejected by the ambiguity policy. See [`ScannerBuilder::try_scan`].
+    #[inline]
+    pub fn build<S: AsRef<[u8]> + Clone>(&self, sequence: S) -> Result<Scanner<S>, Error> {
+        self.try_scan(sequence)
+    }
+
+    /// Scan the provided sequence after validating the parameters.
+    ///
+    /// Unlike [`ScannerBuilder::scan`], this method checks the consistency
+    /// of the scanner parameters first, so that a misconfigured scanner
+    /// fails loudly instead of silently returning no CRISPR region.
+    ///
+    /// # Errors
+    /// Returns an [`Error::Parameter`] naming the offending parameter if the
+    /// parameters are invalid, or an [`Error::AmbiguousBase`] if the
+    /// sequence contains an ambiguous base with the [`Ambiguity::Reject`]
+    /// policy.
+    pub fn try_scan<S: AsRef<[u8]> + Clone>(&self, sequence: S) -> Result<Scanner<S>, Error> {
+        self.validate()?;
+        self.check_sequence(sequence.as_ref())?;
+        Ok(Scanner::with_parameters(sequence, self.clone()))
+    }
+
+    /// Check that the sequence is accepted by the ambiguity policy.
+    fn check_sequence(&self, bytes: &[u8]) -> Result<(), Error> {
+        if self.ambiguity == Ambiguity::Reject {
+            if let Some(position) = bytes.iter().position(|&c| ambiguity::is_ambiguous(c)) {
+                return Err(Error::AmbiguousBase {
+                    position,
+                    base: bytes[position],
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Scan the provided sequence for CRISPR regions using several threads.
+    ///
+    /// The sequence is split into chunks that are searched in parallel,
+    /// and the searches are stitched together at the chunk boundaries so
+    /// that the result is exactly the same as with [`ScannerBuilder::scan`],
+    /// for about the same total amount of work.
+    ///
+    /// The chunks do not overlap: merging the regions found in chunks
+    /// overlapping by the maximum array span would not give the serial
+    /// result, since the serial scanner resumes its search after the end
+    /// of each region, so that the seeds it evaluates, and the regions it
+    /// reports, depend on the regions found before. Instead, the serial
+    /// search is replayed from the end of each chunk until it evaluates
+    /// the same seed as the next chunk, from where both searches agree.
+    ///
+    /// # Errors
+    /// Returns an [`Error::Parameter`] if the parameters are invalid, or
+    /// an [`Error::AmbiguousBase`] if the sequence contains an ambiguous
+    /// base with the [`Ambiguity::Reject`] policy.
+    #[cfg(feature = "rayon")]
+    pub fn par_scan<S>(&self, sequence: S) -> Result<Vec<Crispr<S>>, Error>
+    where
+        S: AsRef<[u8]> + Clone + Send + Sync,
+    {
+        Ok(self.try_scan(sequence)?.collect_parallel())
+    }
+
+    /// Scan only part of the provided sequence for CRISPR regions.
+    ///
+    /// Only the seeds starting in `range` are extended, but the CRISPR
+    /// regions found may extend past the range boundaries if their
+    /// flanks contain more repeats. Coordinates of the returned regions
+    /// are relative to the whole sequence. In circular mode, a region
+    /// crossing the origin is only reported if it overlaps `range`.
+    ///
+    /// # Errors
+    /// Returns an [`Error::Parameter`] if the parameters are invalid, an
+    /// [`Error::ScanRange`] if `range` ends before it starts, or an
+    /// [`Error::AmbiguousBase`] if the sequence contains an ambiguous base
+    /// with the [`Ambiguity::Reject`] policy.
+    pub fn scan_range<S: AsRef<[u8]> + Clone>(
+        &self,
+        sequence: S,
+        range: Range<usize>,
+    ) -> Result<Scanner<S>, Error> {
+        if range.start > range.end {
+            return Err(Error::ScanRange {
+                start: range.start,
+                end: range.end,
+            });
+        }
+        let mut scanner = self.try_scan(sequence)?;
+        scanner.search_limit = range.end;
+        scanner.seek(range.start);
+        Ok(scanner)
+    }
+
+    /// Scan many sequences for CRISPR regions using a pool of threads.
+    ///
+    /// The `sequences` are given as `(id, sequence)` pairs, and the
+    /// CRISPR regions of each sequence are yielded together with its
+    /// identifier, in the same order as the input. Only a few sequences
+    /// per thread are read in advance from `sequences`, so that memory
+    /// usage stays bounded even for very large inputs.
+    ///
+    /// Since the sequences are sent to other threads, they must be owned
+    /// by a cheaply-cloned type such as `Arc<[u8]>` or `&'static [u8]`.
+    ///
+    /// # Errors
+    /// Every sequence is yielded with an [`Error::Parameter`] instead of
+    /// its CRISPR regions if the parameters are invalid, and a sequence
+    /// containing an ambiguous base with the [`Ambiguity::Reject`] policy
+    /// is yielded with an [`Error::AmbiguousBase`].
+    ///
+    /// # Panic
+    /// If scanning a sequence panics, the panic is propagated when the
+    /// CRISPR regions of that sequence are yielded.
+    pub fn scan_many<I, K, S>(&self, sequences: I) -> ScanMany<I::IntoIter, K, S>
+    where
+        I: IntoIterator<Item = (K, S)>,
+        S: AsRef<[u8]> + Clone + Send + 'static,
+    {
+        let threads = match self.threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        ScanMany::new(self, sequences.into_iter(), threads)
+    }
+
+    /// Scan a sequence read from a reader for CRISPR regions iteratively.
+    ///
+    /// The reader must contain a single raw sequence, possibly split
+    /// over several lines: ASCII whitespace is skipped. Only a sliding
+    /// window of the sequence is kept in memory (see
+    /// [`ScannerBuilder::max_array_span`]), and each region is yielded
+    /// with a copy of its own sequence, with coordinates relative to the
+    /// start of the reader.
+    ///
+    /// # Errors
+    /// The iterator yields an error of kind [`std::io::ErrorKind::InvalidInput`]
+    /// wrapping an [`Error::Parameter`] if the parameters are invalid, or an
+    /// [`Error::CircularStream`] if circular mode is enabled, since the end
+    /// of the sequence is not known when its start is scanned. It yields an
+    /// error of kind [`std::io::ErrorKind::InvalidData`] wrapping an
+    /// [`Error::AmbiguousBase`] if the sequence contains an ambiguous base
+    /// with the [`Ambiguity::Reject`] policy.
+    pub fn scan_reader<R: BufRead>(&self, reader: R) -> StreamScanner<R> {
+        StreamScanner::new(self.clone(), reader)
+    }
+
+    /// Compute the significance of CRISPR regions found in a sequence.
+    ///
+    /// The `sequence` is shuffled [`ScannerBuilder::null_shuffles`] times
+    /// while preserving its dinucleotide composition, and each shuffle is
+    /// scanned with the same parameters. The [`Evidence::score`] of each
+    /// CRISPR region in `crisprs` is then compared to the scores of the
+    /// regions found in the shuffles, which are false positives.
+    ///
+    /// The `sequence` can be the whole contig the regions were found in,
+    /// or only a region around them to reduce the cost of the shuffles.
+    /// Results are reproducible for a given [`ScannerBuilder::null_seed`].
+    ///
+    /// # Errors
+    /// Returns an [`Error::Parameter`] if the parameters are invalid, or
+    /// an [`Error::AmbiguousBase`] if the sequence contains an ambiguous
+    /// base with the [`Ambiguity::Reject`] policy.
+    pub fn significance<T: AsRef<[u8]>>(
+        &self,
+        sequence: impl AsRef<[u8]>,
+        crisprs: &[Crispr<T>],
+    ) -> Result<Vec<Significance>, Error> {
+        self.validate()?;
+        self.check_sequence(sequence.as_ref())?;
+        Ok(significance::significance(self, sequence.as_ref(), crisprs))
+    }
+
+    /// Check that the scanner parameters are consistent.
+    ///
+    /// All the parameters constraining the scan are checked. The number
+    /// of [`ScannerBuilder::threads`], the parameters of
+    /// [`ScannerBuilder::significance`] and the flags accept any value.
+    pub fn validate(&self) -> Result<(), ParameterError> {
+        if self.min_repeat_count < 2 {
+            return Err(ParameterError::MinRepeatCount(self.min_repeat_count));
+        }
+        if self.min_repeat_length > self.max_repeat_length {
+            return Err(ParameterError::RepeatLength {
+                min: self.min_repeat_length,
+                max: self.max_repeat_length,
+            });
+        }
+        if self.min_spacer_length > self.max_spacer_length {
+            return Err(ParameterError::SpacerLength {
+                min: self.min_spacer_length,
+                max: self.max_spacer_length,
+            });
+        }
+        if self.search_window_length == 0 || self.search_window_length > self.min_repeat_length {
+            return Err(ParameterError::SearchWindowLength(
+                self.search_window_length,
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.repeat_threshold) {
+            return Err(ParameterError::RepeatThreshold(self.repeat_threshold));
+        }
+        if !(0.0..=1.0).contains(&self.spacer_to_spacer_max_similarity) {
+            return Err(ParameterError::SpacerToSpacerMaxSimilarity(
+                self.spacer_to_spacer_max_similarity,
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.flank_confidence) {
+            return Err(ParameterError::FlankConfidence(self.flank_confidence));
+        }
+        let max_spacing = self.max_repeat_length + self.max_spacer_length;
+        if self.right_scan_range > max_spacing {
+            return Err(ParameterError::RightScanRange(self.right_scan_range));
+        }
+        if self.flank_scan_range > max_spacing {
+            return Err(ParameterError::FlankScanRange(self.flank_scan_range));
+        }
+        if self.max_repeat_indels >= self.min_repeat_length {
+            return Err(ParameterError::MaxRepeatIndels(self.max_repeat_indels));
+        }
+        if self.mask_size == 0 {
+            return Err(ParameterError::MaskSize(self.mask_size));
+        }
+        if self.max_array_span < max_spacing + self.search_window_length {
+            return Err(ParameterError::MaxArraySpan(self.max_array_span));
+        }
+        Ok(())
+    }
+
     /// Set the minimum repeat number for CRISPR detection.
     pub fn min_repeat_count(&mut self, min_repeat_count: usize) -> &mut Self {
         self.min_repeat_count = min_repeat_count;
@@ -172,6 +553,187 @@ impl ScannerBuilder {
         self.max_spacer_length = max_spacer_length;
         self
     }
+
+    /// Set the length of the window used to seed candidate repeats.
+    pub fn search_window_length(&mut self, search_window_length: usize) -> &mut Self {
+        self.search_window_length = search_window_length;
+        self
+    }
+
+    /// Set the fraction of repeats that must agree on a nucleotide.
+    ///
+    /// This threshold is used when extending repeats to their actual
+    /// length, and when trimming the final repeats: a position is only
+    /// kept if at least this fraction of the repeats share the same
+    /// nucleotide.
+    pub fn repeat_threshold(&mut self, repeat_threshold: f32) -> &mut Self {
+        self.repeat_threshold = repeat_threshold;
+        self
+    }
+
+    /// Set the maximum similarity allowed between spacers.
+    ///
+    /// Candidate regions where two consecutive spacers, or a spacer and
+    /// its preceding repeat, are more similar than this are rejected.
+    pub fn spacer_to_spacer_max_similarity(&mut self, max_similarity: f32) -> &mut Self {
+        self.spacer_to_spacer_max_similarity = max_similarity;
+        self
+    }
+
+    /// Set the maximum length difference allowed between spacers.
+    pub fn spacer_to_spacer_length_diff(&mut self, length_diff: usize) -> &mut Self {
+        self.spacer_to_spacer_length_diff = length_diff;
+        self
+    }
+
+    /// Set the maximum length difference allowed between a spacer and the repeat.
+    pub fn spacer_to_repeat_length_diff(&mut self, length_diff: usize) -> &mut Self {
+        self.spacer_to_repeat_length_diff = length_diff;
+        self
+    }
+
+    /// Set the range around the expected position scanned for new repeats.
+    ///
+    /// This is used when extending a seed to the right with exact copies
+    /// of the search window.
+    pub fn right_scan_range(&mut self, right_scan_range: usize) -> &mut Self {
+        self.right_scan_range = right_scan_range;
+        self
+    }
+
+    /// Set the range around the expected position scanned in the flanks.
+    ///
+    /// This is used when extending a candidate region on both sides with
+    /// approximate copies of the terminal repeats.
+    pub fn flank_scan_range(&mut self, flank_scan_range: usize) -> &mut Self {
+        self.flank_scan_range = flank_scan_range;
+        self
+    }
+
+    /// Set the minimum similarity for a repeat found in the flanks.
+    pub fn flank_confidence(&mut self, flank_confidence: f32) -> &mut Self {
+        self.flank_confidence = flank_confidence;
+        self
+    }
+
+    /// Set the maximum number of indels allowed in a single repeat.
+    ///
+    /// When non-zero, repeats found in the flanks may be shorter or longer
+    /// than the other repeats by up to this many bases, and the boundaries
+    /// of every repeat are realigned against the consensus repeat once the
+    /// region is complete. The default of zero only allows substitutions,
+    /// like MinCED.
+    pub fn max_repeat_indels(&mut self, max_repeat_indels: usize) -> &mut Self {
+        self.max_repeat_indels = max_repeat_indels;
+        self
+    }
+
+    /// Set the length above which homopolymer runs are masked before scanning.
+    ///
+    /// With [`ScannerBuilder::mask_period`], this also applies to other
+    /// low-complexity tandem repeats.
+    pub fn mask_size(&mut self, mask_size: usize) -> &mut Self {
+        self.mask_size = mask_size;
+        self
+    }
+
+    /// Set the longest period of the tandem repeats masked before scanning.
+    ///
+    /// Tandem repeats with a period up to `mask_period` and longer than
+    /// [`ScannerBuilder::mask_size`] are masked, so that seeds are not
+    /// extended in microsatellites such as `(CA)n`, which are a common
+    /// source of false positives. The default of one only masks
+    /// homopolymer runs, like MinCED. The masked regions of a sequence
+    /// can be inspected with [`Scanner::mask`].
+    pub fn mask_period(&mut self, mask_period: usize) -> &mut Self {
+        self.mask_period = mask_period;
+        self
+    }
+
+    /// Set how ambiguous bases in the scanned sequences are handled.
+    pub fn ambiguity(&mut self, ambiguity: Ambiguity) -> &mut Self {
+        self.ambiguity = ambiguity;
+        self
+    }
+
+    /// Set whether nucleotides are matched regardless of their case.
+    ///
+    /// This allows repeats in soft-masked regions of a genome to be
+    /// detected, without copying the sequence. By default, matching is
+    /// case-sensitive, like MinCED.
+    pub fn ignore_case(&mut self, ignore_case: bool) -> &mut Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    /// Set whether the sequences of the returned regions are uppercased.
+    ///
+    /// All the methods returning the sequence of a region, such as
+    /// [`Region::as_bytes`] and [`Crispr::oriented_repeats`], as well as
+    /// region comparisons, then see the uppercase sequence. Regions
+    /// containing lowercase nucleotides hold an uppercase copy of their
+    /// sequence, while the scanned sequence itself is left untouched.
+    pub fn normalize_case(&mut self, normalize_case: bool) -> &mut Self {
+        self.normalize_case = normalize_case;
+        self
+    }
+
+    /// Set the strategy used to find a copy of each seed.
+    ///
+    /// [`Seeding::Index`] finds the same CRISPR regions as the default
+    /// [`Seeding::Scan`], and is faster on large sequences at the cost of
+    /// building an index of the sequence first.
+    pub fn seeding(&mut self, seeding: Seeding) -> &mut Self {
+        self.seeding = seeding;
+        self
+    }
+
+    /// Set the number of threads used by [`ScannerBuilder::scan_many`].
+    ///
+    /// If zero, the number of threads is given by
+    /// [`std::thread::available_parallelism`].
+    pub fn threads(&mut self, threads: usize) -> &mut Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Set the maximum span of the regions found by [`ScannerBuilder::scan_reader`].
+    ///
+    /// The streaming scanner keeps about three times this many bases in
+    /// memory, and finds the same regions as [`ScannerBuilder::scan`] as
+    /// long as they are shorter than this span. In circular mode, this
+    /// also bounds the window searched for a region crossing the origin.
+    pub fn max_array_span(&mut self, max_array_span: usize) -> &mut Self {
+        self.max_array_span = max_array_span;
+        self
+    }
+
+    /// Set the number of shuffled sequences used by [`ScannerBuilder::significance`].
+    ///
+    /// The smallest p-value that can be reported is `1 / (null_shuffles + 1)`.
+    pub fn null_shuffles(&mut self, null_shuffles: usize) -> &mut Self {
+        self.null_shuffles = null_shuffles;
+        self
+    }
+
+    /// Set the seed of the random generator used by [`ScannerBuilder::significance`].
+    pub fn null_seed(&mut self, null_seed: u64) -> &mut Self {
+        self.null_seed = null_seed;
+        self
+    }
+
+    /// Set whether the scanned sequences are circular.
+    ///
+    /// In circular mode, a CRISPR region crossing the origin of the
+    /// sequence is reported as a single region, with an end coordinate
+    /// lower than its start coordinate (see [`Crispr::is_wrapped`]). Such
+    /// a region is yielded last, after all the other regions. Such a region
+    /// is only found if it is shorter than [`ScannerBuilder::max_array_span`]
+    /// and than half of the sequence.
+    pub fn circular(&mut self, circular: bool) -> &mut Self {
+        self.circular = circular;
+        self
+    }
 }
 
 impl Default for ScannerBuilder {
@@ -183,6 +745,25 @@ impl Default for ScannerBuilder {
             min_spacer_length: 26,
             max_spacer_length: 50,
             search_window_length: 8,
+            repeat_threshold: 0.75,
+            spacer_to_spacer_max_similarity: 0.62,
+            spacer_to_spacer_length_diff: 12,
+            spacer_to_repeat_length_diff: 30,
+            right_scan_range: 24,
+            flank_scan_range: 30,
+            flank_confidence: 0.7,
+            max_repeat_indels: 0,
+            mask_size: 100,
+            mask_period: 1,
+            circular: false,
+            ambiguity: Ambiguity::Ignore,
+            ignore_case: false,
+            normalize_case: false,
+            threads: 0,
+            max_array_span: 100_000,
+            null_shuffles: 100,
+            null_seed: 0,
+            seeding: Seeding::Scan,
         }
     }
 }
@@ -194,23 +775,43 @@ pub struct Scanner<S> {
     sequence_length: usize,
     mask_index: usize,
     j: usize,
+    bounds: Range<usize>,
+    index: Option<KmerIndex>,
+    search_start: usize,
+    search_limit: usize,
+    origin: Option<Crispr<S>>,
+    origin_probed: bool,
 }
 
-impl<S: AsRef<str> + Clone> Scanner<S> {
-    const THRESHOLD: f32 = 0.75;
-    const SPACER_TO_SPACER_MAX_SIMILARITY: f32 = 0.62;
-    const SPACER_TO_SPACER_LENGTH_DIFF: usize = 12;
-    const SPACER_TO_REPEAT_LENGTH_DIFF: usize = 30;
-
+impl<S: AsRef<[u8]> + Clone> Scanner<S> {
     #[inline]
     pub fn new(sequence: S) -> Self {
-        let seq = Sequence::new(sequence);
+        Self::with_parameters(sequence, ScannerBuilder::default())
+    }
+
+    #[inline]
+    fn with_parameters(sequence: S, parameters: ScannerBuilder) -> Self {
+        let seq = Sequence::new(sequence, parameters.mask_size, parameters.mask_period);
+        let index = match parameters.seeding {
+            Seeding::Index => KmerIndex::new(
+                seq.as_ref(),
+                parameters.search_window_length,
+                parameters.ignore_case,
+            ),
+            Seeding::Scan => None,
+        };
         Self {
-            parameters: ScannerBuilder::default(),
+            parameters,
             sequence_length: seq.len(),
             j: 0,
+            bounds: 0..seq.len(),
+            index,
+            search_start: 0,
+            search_limit: usize::MAX,
             sequence: seq,
             mask_index: 0,
+            origin: None,
+            origin_probed: false,
         }
     }
 
@@ -219,32 +820,115 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
         &self.sequence
     }
 
-    fn _similarity<S1: AsRef<str>, S2: AsRef<str>>(s1: S1, s2: S2) -> f32 {
+    /// Get the low-complexity regions masked before scanning.
+    ///
+    /// Seeds are not extended if their copy would be searched in a
+    /// masked region. See [`ScannerBuilder::mask_size`] and
+    /// [`ScannerBuilder::mask_period`].
+    pub fn mask(&self) -> &[Region<S>] {
+        let mask = &self.sequence.mask;
+        &mask[..mask.len() - 1]
+    }
+
+    /// Move the scanner so that the next seed is searched at `pos`.
+    ///
+    /// The search can be moved backwards as well as forwards, and the
+    /// CRISPR regions found afterwards keep coordinates relative to the
+    /// whole sequence. Moving backwards may yield the same regions again.
+    pub fn seek(&mut self, pos: usize) {
+        self.search_start = pos;
+        self.j = pos.max(self.bounds.start);
+        self.mask_index = 0;
+    }
+
+    /// Look for a CRISPR region crossing the origin of a circular sequence.
+    ///
+    /// A window spanning the origin, of up to the maximum array span on
+    /// each side, is scanned linearly for a region overlapping the origin.
+    /// If one is found, it is kept to be yielded last, and the rest of the
+    /// sequence is only scanned between the end and the start of that region.
+    fn _probe_origin(&mut self) {
+        self.origin_probed = true;
+
+        let bytes = self.sequence.as_ref();
+        let length = bytes.len();
+        let mid = length / 2;
+        if mid == 0 {
+            return;
+        }
+
+        let span = self.parameters.max_array_span.max(
+            self.parameters.max_repeat_length
+                + self.parameters.max_spacer_length
+                + self.parameters.search_window_length,
+        );
+        let before = span.min(length - mid);
+        let after = span.min(mid);
+        let offset = length - before;
+
+        let window = [&bytes[offset..], &bytes[..after]].concat();
+        let mut parameters = self.parameters.clone();
+        parameters.circular = false;
+        let found = Scanner::with_parameters(window.as_slice(), parameters)
+            .take_while(|crispr| crispr.start() < before)
+            .find(|crispr| crispr.end() > before);
+
+        if let Some(window_crispr) = found {
+            let mut crispr = Crispr::new(self.sequence.sequence.clone());
+            crispr.indices = window_crispr.indices.iter().map(|i| i + offset).collect();
+            crispr.ends = window_crispr.ends.iter().map(|i| i + offset).collect();
+            crispr.repeat_length = window_crispr.repeat_length;
+            crispr.strand = window_crispr.strand;
+            crispr.strand_confidence = window_crispr.strand_confidence;
+            crispr.uppercase = window_crispr.uppercase;
+
+            self.bounds = crispr.end()..crispr.start();
+            self.j = self.j.max(self.bounds.start);
+            self.origin = Some(crispr);
+        }
+    }
+
+    fn _similarity<S1: AsRef<[u8]>, S2: AsRef<[u8]>>(&self, s1: S1, s2: S2) -> f32 {
         let s1 = s1.as_ref();
         let s2 = s2.as_ref();
         let max_len = s1.len().max(s2.len());
-        1.0 - ((strsim::levenshtein(s1, s2) as f32) / (max_len as f32))
+        let d = if self.parameters.ignore_case {
+            distance::levenshtein_by(s1, s2, |x, y| x.eq_ignore_ascii_case(&y))
+        } else {
+            distance::levenshtein(s1, s2)
+        };
+        1.0 - ((d as f32) / (max_len as f32))
     }
 
-    fn _hamming<S1: AsRef<str>, S2: AsRef<str>>(s1: S1, s2: S2) -> usize {
+    /// Compute the similarity between two repeats, using IUPAC wildcards if enabled.
+    fn _repeat_similarity<S1: AsRef<[u8]>, S2: AsRef<[u8]>>(&self, s1: S1, s2: S2) -> f32 {
+        if self.parameters.ambiguity != Ambiguity::Wildcard {
+            return self._similarity(s1, s2);
+        }
         let s1 = s1.as_ref();
         let s2 = s2.as_ref();
-        if s1.len() == s2.len() {
-            strsim::hamming(s1, s2).unwrap()
+        let max_len = s1.len().max(s2.len());
+        let d = distance::levenshtein_by(s1, s2, ambiguity::is_compatible);
+        1.0 - ((d as f32) / (max_len as f32))
+    }
+
+    /// Find the offset of the window of `text` closest to `pattern`, with its distance.
+    fn _best_window(&self, pattern: &[u8], text: &[u8]) -> Option<(usize, usize)> {
+        if self.parameters.ambiguity == Ambiguity::Wildcard {
+            distance::best_hamming_window_by(pattern, text, ambiguity::is_compatible)
+        } else if self.parameters.ignore_case {
+            distance::best_hamming_window_by(pattern, text, |x, y| x.eq_ignore_ascii_case(&y))
         } else {
-            let l = s1.len().min(s2.len());
-            let d = s1.len().abs_diff(s2.len());
-            strsim::hamming(&s1[..l], &s2[..l]).unwrap() + d
+            distance::best_hamming_window(pattern, text)
         }
     }
 
     fn _scan_right(&self, crispr: &mut Crispr<S>, pattern: &[u8], scan_range: usize) {
-        let seq = crispr.sequence.as_ref();
-        let bytes = seq.as_bytes();
+        let bytes = crispr.sequence.as_ref();
 
         let num_repeats = crispr.indices.len();
         let pattern_len = pattern.len();
-        let sequence_len = seq.len();
+        let sequence_len = bytes.len();
 
         #[cfg(feature = "memchr")]
         let finder = memchr::memmem::Finder::new(pattern);
@@ -273,9 +957,17 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
             let subseq = &bytes[begin_search..end_search];
 
             #[cfg(feature = "memchr")]
-            let pos = finder.find(subseq);
+            let pos = if self.parameters.ignore_case {
+                memmem_ignore_case(subseq, pattern)
+            } else {
+                finder.find(subseq)
+            };
             #[cfg(not(feature = "memchr"))]
-            let pos = subseq.find(pattern);
+            let pos = if self.parameters.ignore_case {
+                memmem_ignore_case(subseq, pattern)
+            } else {
+                memmem(subseq, pattern)
+            };
 
             if let Some(k) = pos {
                 crispr.indices.push(begin_search + k);
@@ -306,13 +998,13 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
         let sequence_len = seq.len();
         let mut char_counts = DnaCount::new();
 
-        let mut right_extension_length = self.parameters.search_window_length;
+        let mut right_extension_length = self.parameters.search_window_length.max(1);
         let max_right_extension_length =
             shortest_repeat_spacing.saturating_sub(self.parameters.min_spacer_length);
 
         while right_extension_length <= max_right_extension_length {
             if last_repeat_start_index + right_extension_length >= sequence_len {
-                if crispr.indices.len() > self.parameters.min_repeat_count + 1 {
+                if crispr.indices.len() > self.parameters.min_repeat_count.max(1) + 1 {
                     crispr.indices.pop().unwrap();
                     last_repeat_start_index = *crispr.indices.last().unwrap();
                 } else {
@@ -321,20 +1013,19 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
             }
             for k in 0..crispr.indices.len() {
                 let current_repeat_start_index = crispr.indices[k];
-                let last_char =
-                    seq.as_bytes()[current_repeat_start_index + right_extension_length - 1];
-                char_counts.count(last_char as char);
+                let last_char = seq[current_repeat_start_index + right_extension_length - 1];
+                char_counts.count(last_char);
             }
-            if ((char_counts.max() as f32) / (crispr.indices.len() as f32)) >= Self::THRESHOLD {
+            if ((char_counts.max() as f32) / (crispr.indices.len() as f32))
+                >= self.parameters.repeat_threshold
+            {
                 right_extension_length += 1;
                 char_counts.clear();
             } else {
                 break;
             }
         }
-        if right_extension_length > 0 {
-            right_extension_length -= 1;
-        }
+        right_extension_length = right_extension_length.saturating_sub(1);
         char_counts.clear();
 
         let mut left_extension_length = 0;
@@ -343,7 +1034,7 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
             .saturating_sub(right_extension_length);
         while left_extension_length <= max_left_extension_length {
             if first_repeat_start_index < left_extension_length {
-                if crispr.indices.len() > self.parameters.min_repeat_count + 1 {
+                if crispr.indices.len() > self.parameters.min_repeat_count.max(1) + 1 {
                     crispr.indices.remove(0); // FIXME: use VecDeque?
                     first_repeat_start_index = *crispr.indices.first().unwrap();
                 } else {
@@ -352,25 +1043,30 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
             }
             for k in 0..crispr.indices.len() {
                 let current_repeat_start_index = crispr.indices[k];
-                let first_char = seq.as_bytes()[current_repeat_start_index - left_extension_length];
-                char_counts.count(first_char as char)
+                let first_char = seq[current_repeat_start_index - left_extension_length];
+                char_counts.count(first_char)
             }
-            if (char_counts.max() as f32) / (crispr.indices.len() as f32) >= Self::THRESHOLD {
+            if (char_counts.max() as f32) / (crispr.indices.len() as f32)
+                >= self.parameters.repeat_threshold
+            {
                 left_extension_length += 1;
                 char_counts.clear();
             } else {
                 break;
             }
         }
-        if left_extension_length > 0 {
-            left_extension_length -= 1;
-        }
+        left_extension_length = left_extension_length.saturating_sub(1);
 
         for index in crispr.indices.iter_mut() {
             *index -= left_extension_length;
         }
 
         crispr.repeat_length = right_extension_length + left_extension_length;
+        crispr.ends = crispr
+            .indices
+            .iter()
+            .map(|&i| i + crispr.repeat_length)
+            .collect();
     }
 
     fn _has_non_repeating_spacers(&self, crispr: &Crispr<S>) -> bool {
@@ -386,26 +1082,26 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
                 let current_spacer = crispr.spacer(i);
                 let next_spacer = crispr.spacer(i + 1);
                 let current_repeat = crispr.repeat(i);
-                if Self::_similarity(&current_spacer, &next_spacer)
-                    > Self::SPACER_TO_SPACER_MAX_SIMILARITY
+                if self._similarity(current_spacer.to_bytes(), next_spacer.to_bytes())
+                    > self.parameters.spacer_to_spacer_max_similarity
                 {
                     return false;
                 }
-                if Self::_similarity(&current_repeat, &current_spacer)
-                    > Self::SPACER_TO_SPACER_MAX_SIMILARITY
+                if self._similarity(current_repeat.to_bytes(), current_spacer.to_bytes())
+                    > self.parameters.spacer_to_spacer_max_similarity
                 {
                     return false;
                 }
                 i += 1;
             }
-            Self::_similarity(crispr.repeat(i), crispr.spacer(i))
-                <= Self::SPACER_TO_SPACER_MAX_SIMILARITY
+            self._similarity(crispr.repeat(i).to_bytes(), crispr.spacer(i).to_bytes())
+                <= self.parameters.spacer_to_spacer_max_similarity
         } else if crispr.indices.len() == 2 {
             if first_spacer.is_empty() {
                 false
             } else {
-                Self::_similarity(first_spacer, first_repeat)
-                    < Self::SPACER_TO_SPACER_MAX_SIMILARITY
+                self._similarity(first_spacer.to_bytes(), first_repeat.to_bytes())
+                    < self.parameters.spacer_to_spacer_max_similarity
             }
         } else {
             false
@@ -418,11 +1114,13 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
         for i in 0..crispr.indices.len() - 1 {
             let current_spacer_length = crispr.spacer(i).len();
             if current_spacer_length.abs_diff(initial_spacer_length)
-                > Self::SPACER_TO_SPACER_LENGTH_DIFF
+                > self.parameters.spacer_to_spacer_length_diff
             {
                 return false;
             }
-            if current_spacer_length.abs_diff(repeat_length) > Self::SPACER_TO_REPEAT_LENGTH_DIFF {
+            if current_spacer_length.abs_diff(repeat_length)
+                > self.parameters.spacer_to_repeat_length_diff
+            {
                 return false;
             }
         }
@@ -436,10 +1134,16 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
         scan_range: usize,
         confidence: f32,
     ) {
-        while let Some(pos) = self._scan(crispr, flank, scan_range, confidence) {
+        while let Some((start, end)) = self._scan(crispr, flank, scan_range, confidence) {
             match flank {
-                Flank::Left => crispr.indices.insert(0, pos),
-                Flank::Right => crispr.indices.push(pos),
+                Flank::Left => {
+                    crispr.indices.insert(0, start);
+                    crispr.ends.insert(0, end);
+                }
+                Flank::Right => {
+                    crispr.indices.push(start);
+                    crispr.ends.push(end);
+                }
             }
         }
     }
@@ -450,21 +1154,21 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
         flank: Flank,
         scan_range: usize,
         confidence: f32,
-    ) -> Option<usize> {
-        let repeat_length = crispr.repeat_length;
+    ) -> Option<(usize, usize)> {
         let num_repeats = crispr.indices.len();
         let seq = crispr.sequence.as_ref();
         let sequence_len = seq.len();
 
         let first_repeat_index = *crispr.indices.first().unwrap();
         let last_repeat_index = *crispr.indices.last().unwrap();
+        let last_repeat_end = *crispr.ends.last().unwrap();
 
-        let repeat_string;
+        let repeat;
         let candidate_repeat_index;
 
         match flank {
             Flank::Left => {
-                repeat_string = crispr.repeat(0);
+                repeat = crispr.repeat(0);
                 let repeat_spacing = if num_repeats >= 3 {
                     (crispr.repeat_spacing(0) + crispr.repeat_spacing(1)) / 2
                 } else {
@@ -473,7 +1177,7 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
                 candidate_repeat_index = first_repeat_index.saturating_sub(repeat_spacing);
             }
             Flank::Right => {
-                repeat_string = crispr.repeat(num_repeats - 1);
+                repeat = crispr.repeat(num_repeats - 1);
                 let repeat_spacing = if num_repeats >= 3 {
                     (crispr.repeat_spacing(num_repeats - 2)
                         + crispr.repeat_spacing(num_repeats - 3))
@@ -484,6 +1188,8 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
                 candidate_repeat_index = last_repeat_index + repeat_spacing;
             }
         };
+        let repeat_string = repeat.to_bytes();
+        let repeat_length = repeat_string.len();
 
         if candidate_repeat_index < scan_range {
             return None;
@@ -495,8 +1201,7 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
         let scan_left_max_end = first_repeat_index
             .saturating_sub(repeat_length)
             .saturating_sub(self.parameters.min_spacer_length);
-        let scan_right_min_begin =
-            last_repeat_index + repeat_length + self.parameters.min_spacer_length;
+        let scan_right_min_begin = last_repeat_end + self.parameters.min_spacer_length;
 
         match flank {
             Flank::Left => {
@@ -521,15 +1226,96 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
             return None;
         }
 
-        let new_candidate_repeat_index = (begin..=end)
-            .min_by_key(|&i| Self::_hamming(&repeat_string, &seq[i..i + repeat_length]))
-            .unwrap();
+        let (offset, _) = self._best_window(&repeat_string, &seq[begin..end + repeat_length])?;
+        let new_candidate_repeat_index = begin + offset;
+        let new_candidate_repeat_end = new_candidate_repeat_index + repeat_length;
         let new_candidate_repeat_string =
-            &seq[new_candidate_repeat_index..new_candidate_repeat_index + repeat_length];
-        if Self::_similarity(&repeat_string, new_candidate_repeat_string) >= confidence {
-            Some(new_candidate_repeat_index)
-        } else {
-            None
+            &seq[new_candidate_repeat_index..new_candidate_repeat_end];
+        if self._repeat_similarity(&repeat_string, new_candidate_repeat_string) >= confidence {
+            return Some((new_candidate_repeat_index, new_candidate_repeat_end));
+        }
+
+        // look for a repeat with indels around the best ungapped candidate,
+        // without overlapping the spacer lengths enforced above
+        let indels = self.parameters.max_repeat_indels;
+        if indels == 0 {
+            return None;
+        }
+        let (min_begin, max_end) = match flank {
+            Flank::Left => (0, scan_left_max_end + repeat_length),
+            Flank::Right => (scan_right_min_begin, sequence_len),
+        };
+        let mut best = None;
+        let mut best_similarity = confidence;
+        let starts = new_candidate_repeat_index
+            .saturating_sub(indels)
+            .max(min_begin)..=new_candidate_repeat_index + indels;
+        for start in starts {
+            let lengths = repeat_length.saturating_sub(indels).max(1)..=repeat_length + indels;
+            for length in lengths {
+                if start + length > max_end {
+                    break;
+                }
+                let similarity =
+                    self._repeat_similarity(&repeat_string, &seq[start..start + length]);
+                if similarity > best_similarity || (best.is_none() && similarity >= confidence) {
+                    best = Some((start, start + length));
+                    best_similarity = similarity;
+                }
+            }
+        }
+        best
+    }
+
+    /// Truncate a CRISPR region before its first ambiguous base.
+    fn _break(&self, crispr: &mut Crispr<S>) {
+        let start = crispr.indices[0];
+        let end = *crispr.ends.last().unwrap();
+        let region = crispr.slice(start, end);
+        if let Some(i) = region.iter().position(|&c| ambiguity::is_ambiguous(c)) {
+            let n = crispr.ends.iter().take_while(|&&e| e <= start + i).count();
+            crispr.indices.truncate(n);
+            crispr.ends.truncate(n);
+        }
+    }
+
+    /// Realign the boundaries of each repeat against the consensus repeat.
+    ///
+    /// Each repeat boundary may move by up to `max_repeat_indels` bases,
+    /// and is only moved if this strictly reduces the edit distance to
+    /// the consensus, so that ungapped repeats keep their coordinates.
+    fn _realign(&self, crispr: &mut Crispr<S>) {
+        let indels = self.parameters.max_repeat_indels;
+        if indels == 0 {
+            return;
+        }
+
+        let consensus = crispr.consensus_repeat();
+        let seq = crispr.sequence.as_ref();
+        for k in 0..crispr.indices.len() {
+            let min_start = if k == 0 { 0 } else { crispr.ends[k - 1] };
+            let max_end = crispr.indices.get(k + 1).cloned().unwrap_or(seq.len());
+            let (start, end) = (crispr.indices[k], crispr.ends[k]);
+
+            let mut best = (start, end);
+            let mut best_distance =
+                distance::levenshtein(&seq[start..end].to_ascii_uppercase(), &consensus);
+            for new_start in start.saturating_sub(indels).max(min_start)..=start + indels {
+                for new_end in end.saturating_sub(indels)..=(end + indels).min(max_end) {
+                    if new_end <= new_start {
+                        continue;
+                    }
+                    let repeat = seq[new_start..new_end].to_ascii_uppercase();
+                    let d = distance::levenshtein(&repeat, &consensus);
+                    if d < best_distance {
+                        best = (new_start, new_end);
+                        best_distance = d;
+                    }
+                }
+            }
+
+            crispr.indices[k] = best.0;
+            crispr.ends[k] = best.1;
         }
     }
 
@@ -541,9 +1327,13 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
             for k in 0..num_repeats {
                 let repeat = crispr.repeat(k);
                 let last_char = repeat.as_bytes().last().unwrap();
-                char_counts.count(*last_char as char);
+                char_counts.count(*last_char);
             }
-            if (char_counts.max() as f32) / (num_repeats as f32) < Self::THRESHOLD {
+            if (char_counts.max() as f32) / (num_repeats as f32) < self.parameters.repeat_threshold
+            {
+                for end in crispr.ends.iter_mut() {
+                    *end -= 1;
+                }
                 crispr.repeat_length -= 1;
                 char_counts.clear();
             } else {
@@ -556,9 +1346,10 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
             for k in 0..num_repeats {
                 let repeat = crispr.repeat(k);
                 let first_char = repeat.as_bytes().first().unwrap();
-                char_counts.count(*first_char as char);
+                char_counts.count(*first_char);
             }
-            if (char_counts.max() as f32) / (num_repeats as f32) < Self::THRESHOLD {
+            if (char_counts.max() as f32) / (num_repeats as f32) < self.parameters.repeat_threshold
+            {
                 for index in crispr.indices.iter_mut() {
                     *index += 1;
                 }
@@ -571,90 +1362,190 @@ impl<S: AsRef<str> + Clone> Scanner<S> {
     }
 }
 
-impl<S: AsRef<str> + Clone> Iterator for Scanner<S> {
+impl<S: AsRef<[u8]> + Clone> Iterator for Scanner<S> {
     type Item = Crispr<S>;
     fn next(&mut self) -> Option<Self::Item> {
-        let seq = self.sequence.as_ref();
-        let bytes = seq.as_bytes();
+        if self.parameters.circular && !self.origin_probed {
+            self._probe_origin();
+        }
+        let (start, limit) = (self.search_start, self.search_limit);
+        self._next_linear(Self::_seed).or_else(|| {
+            self.origin
+                .take()
+                .filter(|crispr| crispr.start() < limit || crispr.end() > start)
+        })
+    }
+}
 
-        let skips = self
-            .parameters
+impl<S: AsRef<[u8]> + Clone> Scanner<S> {
+    /// Get the number of positions skipped between two seeds.
+    fn _skips(&self) -> usize {
+        self.parameters
             .min_repeat_length
-            .checked_sub(2 * self.parameters.search_window_length - 1)
-            .unwrap_or(1);
+            .saturating_sub((2 * self.parameters.search_window_length).saturating_sub(1))
+            .max(1)
+    }
 
-        let search_end = seq
-            .len()
+    /// Get the position where the search for new seeds stops.
+    fn _search_end(&self) -> usize {
+        self.sequence_length
             .saturating_sub(self.parameters.max_repeat_length)
             .saturating_sub(self.parameters.max_spacer_length)
-            .saturating_sub(self.parameters.search_window_length);
-
-        while self.j < search_end {
-            let mut begin_search =
-                self.j + self.parameters.min_repeat_length + self.parameters.min_spacer_length;
-            let mut end_search = self.j
-                + self.parameters.max_repeat_length
-                + self.parameters.max_spacer_length
-                + self.parameters.search_window_length;
-            if begin_search > self.sequence_length {
-                begin_search = self.sequence_length;
-            }
-            if end_search > self.sequence_length {
-                end_search = self.sequence_length;
-            }
-            if end_search < begin_search {
-                end_search = begin_search;
-            }
+            .saturating_sub(self.parameters.search_window_length)
+            .min(self.bounds.end)
+            .min(self.search_limit)
+    }
 
-            if self
-                .sequence
-                ._is_masked(&mut self.mask_index, begin_search, end_search)
+    /// Get the range searched for a copy of the seed at position `j`.
+    fn _search_window(&self, j: usize) -> (usize, usize) {
+        let mut begin_search =
+            j + self.parameters.min_repeat_length + self.parameters.min_spacer_length;
+        let mut end_search = j
+            + self.parameters.max_repeat_length
+            + self.parameters.max_spacer_length
+            + self.parameters.search_window_length;
+        if begin_search > self.sequence_length {
+            begin_search = self.sequence_length;
+        }
+        if end_search > self.sequence_length {
+            end_search = self.sequence_length;
+        }
+        if end_search < begin_search {
+            end_search = begin_search;
+        }
+        (begin_search, end_search)
+    }
+
+    /// Find the first copy of the seed `pattern` in `haystack`.
+    fn _memmem(&self, haystack: &[u8], pattern: &[u8]) -> Option<usize> {
+        if self.parameters.ignore_case {
+            memmem_ignore_case(haystack, pattern)
+        } else {
+            #[cfg(feature = "memchr")]
+            return memchr::memmem::find(haystack, pattern);
+            #[cfg(not(feature = "memchr"))]
+            return memmem(haystack, pattern);
+        }
+    }
+
+    /// Extend the seed at position `j` into a CRISPR region, if any.
+    ///
+    /// This only depends on the seed position and not on the scanner
+    /// state, so that seeds can be evaluated independently.
+    fn _seed(&self, j: usize) -> Option<Crispr<S>> {
+        let bytes = self.sequence.as_ref();
+        let (begin_search, end_search) = self._search_window(j);
+
+        let pattern_start = j;
+        let pattern_end = (j + self.parameters.search_window_length).min(bytes.len());
+
+        let pattern = &bytes[pattern_start..pattern_end];
+        let subseq = &bytes[begin_search..end_search];
+
+        let indexed = self
+            .index
+            .as_ref()
+            .and_then(|index| index.find(bytes, j, begin_search, end_search));
+        let pos = match indexed {
+            Some(found) => found.map(|k| k - begin_search),
+            None => self._memmem(subseq, pattern),
+        };
+
+        let mut candidate_crispr = Crispr::new(self.sequence.clone());
+        candidate_crispr.uppercase = self.parameters.normalize_case;
+        if let Some(k) = pos {
+            candidate_crispr.indices.push(j);
+            candidate_crispr.indices.push(begin_search + k);
+            self._scan_right(
+                &mut candidate_crispr,
+                pattern,
+                self.parameters.right_scan_range,
+            );
+        }
+
+        if !candidate_crispr.indices.is_empty()
+            && candidate_crispr.indices.len() >= self.parameters.min_repeat_count
+        {
+            self._get_actual_repeat_length(&mut candidate_crispr);
+            let actual_repeat_length = candidate_crispr.repeat_length;
+
+            if actual_repeat_length >= self.parameters.min_repeat_length
+                && actual_repeat_length <= self.parameters.max_repeat_length
+                && self._has_non_repeating_spacers(&candidate_crispr)
+                && self._has_similarly_sized_spacers(&candidate_crispr)
             {
-                self.j = self.sequence.mask[self.mask_index].end();
-                if self.j >= search_end {
+                let scan_range = self.parameters.flank_scan_range;
+                let confidence = self.parameters.flank_confidence;
+                self._check_flank(&mut candidate_crispr, Flank::Left, scan_range, confidence);
+                self._check_flank(&mut candidate_crispr, Flank::Right, scan_range, confidence);
+                self._trim(&mut candidate_crispr);
+                self._realign(&mut candidate_crispr);
+                if self.parameters.ambiguity == Ambiguity::Break {
+                    self._break(&mut candidate_crispr);
+                    if candidate_crispr.len() < self.parameters.min_repeat_count.max(2) {
+                        return None;
+                    }
+                }
+                if candidate_crispr.start() < self.bounds.start
+                    || candidate_crispr.end() > self.bounds.end
+                {
                     return None;
                 }
-                continue;
+                (candidate_crispr.strand, candidate_crispr.strand_confidence) =
+                    strand::predict(&candidate_crispr);
+                return Some(candidate_crispr);
             }
+        }
 
-            let pattern_start = self.j;
-            let pattern_end = (self.j + self.parameters.search_window_length).min(seq.len());
-
-            let pattern = &bytes[pattern_start..pattern_end];
-            let subseq = &bytes[begin_search..end_search];
+        None
+    }
 
-            #[cfg(feature = "memchr")]
-            let pos = memchr::memmem::find(subseq, pattern);
-            #[cfg(not(feature = "memchr"))]
-            let pos = subseq.find(pattern);
+    /// Get the next linear CRISPR region, using `seed` to extend seeds.
+    fn _next_linear<F>(&mut self, seed: F) -> Option<Crispr<S>>
+    where
+        F: FnMut(&Self, usize) -> Option<Crispr<S>>,
+    {
+        let (mut j, mut mask_index) = (self.j, self.mask_index);
+        let crispr = self._next_linear_from(&mut j, &mut mask_index, self._search_end(), seed);
+        (self.j, self.mask_index) = (j, mask_index);
+        crispr
+    }
 
-            let mut candidate_crispr = Crispr::new(self.sequence.clone());
-            if let Some(k) = pos {
-                candidate_crispr.indices.push(self.j);
-                candidate_crispr.indices.push(begin_search + k);
-                self._scan_right(&mut candidate_crispr, pattern, 24);
-            }
-
-            if candidate_crispr.indices.len() >= self.parameters.min_repeat_count {
-                self._get_actual_repeat_length(&mut candidate_crispr);
-                let actual_repeat_length = candidate_crispr.repeat_length;
-
-                if actual_repeat_length >= self.parameters.min_repeat_length {
-                    if actual_repeat_length <= self.parameters.max_repeat_length {
-                        if self._has_non_repeating_spacers(&candidate_crispr) {
-                            if self._has_similarly_sized_spacers(&candidate_crispr) {
-                                self._check_flank(&mut candidate_crispr, Flank::Left, 30, 0.7);
-                                self._check_flank(&mut candidate_crispr, Flank::Right, 30, 0.7);
-                                self._trim(&mut candidate_crispr);
-                                self.j = candidate_crispr.end();
-                                return Some(candidate_crispr);
-                            }
-                        }
-                    }
+    /// Get the next linear CRISPR region seeded before `search_end`.
+    ///
+    /// The search position and the mask cursor are given explicitly, so
+    /// that several parts of the sequence can be searched independently.
+    fn _next_linear_from<F>(
+        &self,
+        j: &mut usize,
+        mask_index: &mut usize,
+        search_end: usize,
+        mut seed: F,
+    ) -> Option<Crispr<S>>
+    where
+        F: FnMut(&Self, usize) -> Option<Crispr<S>>,
+    {
+        let skips = self._skips();
+
+        while *j < search_end {
+            let (begin_search, end_search) = self._search_window(*j);
+            if self
+                .sequence
+                ._is_masked(mask_index, begin_search, end_search)
+            {
+                *j = self.sequence.mask[*mask_index].end();
+                if *j >= search_end {
+                    return None;
                 }
+                continue;
+            }
+
+            if let Some(crispr) = seed(self, *j) {
+                *j = crispr.end();
+                return Some(crispr);
             }
 
-            self.j += skips;
+            *j += skips;
         }
 
         None
@@ -666,7 +1557,12 @@ impl<S: AsRef<str> + Clone> Iterator for Scanner<S> {
 pub struct Crispr<S> {
     sequence: S,
     indices: Vec<usize>,
+    ends: Vec<usize>,
     repeat_length: usize,
+    strand: Strand,
+    strand_confidence: f32,
+    uppercase: bool,
+    offset: usize,
 }
 
 impl<S> Crispr<S> {
@@ -676,39 +1572,194 @@ impl<S> Crispr<S> {
         self.indices.len()
     }
 
+    /// Check whether the CRISPR region contains no repeat.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
     /// Get the start index of the CRISPR region (zero-based).
     ///
     /// This is returned as a zero-based, inclusive index, which can be
     /// used for slicing.
     #[inline]
     pub fn start(&self) -> usize {
-        self.indices.first().cloned().unwrap_or(0)
+        self.offset + self.indices.first().cloned().unwrap_or(0)
     }
 
-    /// Get the end index of the CRISPR region (zero-based, exclusive).
+    /// Get the predicted strand the CRISPR array is transcribed from.
     #[inline]
-    pub fn end(&self) -> usize {
-        self.indices.last().cloned().unwrap_or(0) + self.repeat_length
+    pub fn strand(&self) -> Strand {
+        self.strand
+    }
+
+    /// Get the confidence of the strand prediction, between 0 and 1.
+    #[inline]
+    pub fn strand_confidence(&self) -> f32 {
+        self.strand_confidence
     }
 }
 
-impl<S: AsRef<str>> Crispr<S> {
+impl<S: AsRef<[u8]>> Crispr<S> {
     /// Create a new crispr region for the given sequence.
     #[inline]
     fn new(sequence: S) -> Self {
         Self {
             sequence,
             indices: Vec::new(),
+            ends: Vec::new(),
             repeat_length: 0,
+            strand: Strand::Unknown,
+            strand_confidence: 0.0,
+            uppercase: false,
+            offset: 0,
+        }
+    }
+
+    /// Get the end index of the CRISPR region (zero-based, exclusive).
+    ///
+    /// If the region crosses the origin of a circular sequence, the end
+    /// index is lower than the start index.
+    #[inline]
+    pub fn end(&self) -> usize {
+        let start = self.indices.first().cloned().unwrap_or(0);
+        let end = self.ends.last().cloned().unwrap_or(0);
+        self.offset + self.wrap(start, end).1
+    }
+
+    /// Check whether the CRISPR region crosses the origin of a circular sequence.
+    #[inline]
+    pub fn is_wrapped(&self) -> bool {
+        self.ends.last().cloned().unwrap_or(0) > self.sequence.as_ref().len()
+    }
+
+    /// Get the `k`-th repeat in the direction of transcription.
+    ///
+    /// Repeats are numbered from the leader end of the array, and
+    /// reverse-complemented if the array is on the [`Strand::Minus`]
+    /// strand. Arrays with an unknown strand are returned as scanned.
+    ///
+    /// # Panic
+    /// Panics if `k >= self.len()`.
+    pub fn oriented_repeat(&self, index: usize) -> Cow<'_, [u8]> {
+        let k = match self.strand {
+            Strand::Minus => self.len() - 1 - index,
+            _ => index,
+        };
+        self.oriented(self.indices[k], self.ends[k])
+    }
+
+    /// Get an iterator over the repeats in the direction of transcription.
+    pub fn oriented_repeats(&self) -> impl ExactSizeIterator<Item = Cow<'_, [u8]>> + '_ {
+        (0..self.len()).map(|k| self.oriented_repeat(k))
+    }
+
+    /// Get the `k`-th spacer in the direction of transcription.
+    ///
+    /// # Panic
+    /// Panics if `k + 1 >= self.len()`.
+    pub fn oriented_spacer(&self, index: usize) -> Cow<'_, [u8]> {
+        let k = match self.strand {
+            Strand::Minus => self.len() - 2 - index,
+            _ => index,
+        };
+        self.oriented(self.ends[k], self.indices[k + 1])
+    }
+
+    /// Get an iterator over the spacers in the direction of transcription.
+    pub fn oriented_spacers(&self) -> impl ExactSizeIterator<Item = Cow<'_, [u8]>> + '_ {
+        (0..self.len().saturating_sub(1)).map(|k| self.oriented_spacer(k))
+    }
+
+    /// Get the per-position nucleotide profile of the repeats.
+    ///
+    /// The profile is computed over the repeats as they appear in the
+    /// scanned sequence, regardless of the predicted strand.
+    pub fn profile(&self) -> Profile {
+        Profile::new(
+            self.repeat_length,
+            self.indices
+                .iter()
+                .zip(&self.ends)
+                .map(|(&i, &j)| self.slice(i, j)),
+        )
+    }
+
+    /// Get the consensus repeat of the CRISPR region.
+    ///
+    /// Each position holds the most frequent nucleotide across all
+    /// repeats, in the orientation of the scanned sequence.
+    pub fn consensus_repeat(&self) -> Vec<u8> {
+        self.profile().consensus()
+    }
+
+    /// Get the divergence of each repeat from the consensus repeat.
+    ///
+    /// Mismatches are compared case-insensitively, and the terminal
+    /// repeat at the trailer end is flagged when it is the most degenerate
+    /// one, as it usually marks the inactive end of the array.
+    pub fn degeneracies(&self) -> Vec<Degeneracy> {
+        degeneracy::degeneracies(self)
+    }
+
+    /// Get the evidence supporting the CRISPR region.
+    ///
+    /// The evidence can be used to rank CRISPR regions, and to separate
+    /// confident arrays from borderline calls such as tandem repeats.
+    pub fn evidence(&self) -> Evidence {
+        evidence::evidence(self)
+    }
+
+    fn oriented(&self, start: usize, end: usize) -> Cow<'_, [u8]> {
+        let region = self.slice(start, end);
+        let region = match self.strand {
+            Strand::Minus => Cow::Owned(strand::reverse_complement(&region)),
+            _ => region,
+        };
+        if self.uppercase && region.iter().any(u8::is_ascii_lowercase) {
+            Cow::Owned(region.to_ascii_uppercase())
+        } else {
+            region
+        }
+    }
+
+    /// Get the sequence between two coordinates, wrapping around the origin.
+    ///
+    /// Coordinates of regions crossing the origin of a circular sequence
+    /// are stored past the sequence length, so they can be compared and
+    /// subtracted like linear coordinates.
+    fn slice(&self, start: usize, end: usize) -> Cow<'_, [u8]> {
+        let sequence = self.sequence.as_ref();
+        let length = sequence.len();
+        if end <= length {
+            Cow::Borrowed(&sequence[start..end])
+        } else if start >= length {
+            Cow::Borrowed(&sequence[start - length..end - length])
+        } else {
+            Cow::Owned([&sequence[start..], &sequence[..end - length]].concat())
+        }
+    }
+
+    /// Map unwrapped coordinates back to sequence coordinates.
+    fn wrap(&self, start: usize, end: usize) -> (usize, usize) {
+        let length = self.sequence.as_ref().len();
+        if start >= length {
+            (start - length, end - length)
+        } else if end > length {
+            (start, end - length)
+        } else {
+            (start, end)
         }
     }
 }
 
-impl<S: AsRef<str> + Clone> Crispr<S> {
+impl<S: AsRef<[u8]> + Clone> Crispr<S> {
     /// Get the complete CRISPR region as a [`Region`].
     #[inline]
     pub fn to_region(&self) -> Region<S> {
-        Region::new(self.sequence.clone(), self.start(), self.end())
+        let start = self.indices.first().cloned().unwrap_or(0);
+        let end = self.ends.last().cloned().unwrap_or(0);
+        self.region(start, end)
     }
 
     /// Get the sequence of the `k`-th repeat in the CRISPR region.
@@ -716,9 +1767,7 @@ impl<S: AsRef<str> + Clone> Crispr<S> {
     /// # Panic
     /// Panics if `k >= self.len()`.
     pub fn repeat(&self, index: usize) -> Region<S> {
-        let start = self.indices[index];
-        let end = start + self.repeat_length;
-        Region::new(self.sequence.clone(), start, end)
+        self.region(self.indices[index], self.ends[index])
     }
 
     /// Get an iterator over the repeats of the CRISPR region.
@@ -732,11 +1781,7 @@ impl<S: AsRef<str> + Clone> Crispr<S> {
     /// # Panic
     /// Panics if `k + 1 >= self.len()`.
     pub fn spacer(&self, index: usize) -> Region<S> {
-        let current_end = self.indices[index] + self.repeat_length;
-        let next_start = self.indices[index + 1];
-        let spacer_start = current_end;
-        let spacer_end = next_start;
-        Region::new(self.sequence.clone(), spacer_start, spacer_end)
+        self.region(self.ends[index], self.indices[index + 1])
     }
 
     /// Get an iterator over the spacers of the CRISPR region.
@@ -745,6 +1790,14 @@ impl<S: AsRef<str> + Clone> Crispr<S> {
         Regions::new(self, RegionType::Spacer)
     }
 
+    /// Get the region between two unwrapped coordinates.
+    fn region(&self, start: usize, end: usize) -> Region<S> {
+        let (start, end) = self.wrap(start, end);
+        Region::new(self.sequence.clone(), start, end)
+            .with_offset(self.offset)
+            .with_uppercase(self.uppercase)
+    }
+
     /// Compute the spacing the `k`-th and `k+1`-th repeats.
     ///
     /// # Panic
@@ -760,8 +1813,11 @@ mod tests {
     use super::*;
 
     use std::rc::Rc;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
 
-    const SEQ: &str = concat!(
+    pub(crate) const SEQ: &str = concat!(
         "TTTTACAATCTGCGTTTTAACTCCACACGGTACATTAGAAACCATCTGCAACATATT",
         "CAAGTTCAGCTTCAAAACCTTGTTTTAACTCCACACGGTACATTAGAAACTTCGTCA",
         "AGCTTTACCTCAAAAGTCCTCTCAAACCTGTTTTAACTCCACACGGTACATTAGAAA",
@@ -770,6 +1826,86 @@ mod tests {
         "CCACACGGTACATTAGAAACCCTGCGTGCCTGTGTCTAAAAAATA",
     );
 
+    /// Build a sequence with copies of `SEQ` separated by a pseudo-random sequence.
+    fn spaced(copies: usize) -> String {
+        let mut state = 42u32;
+        let mut filler = String::new();
+        for _ in 0..1000 {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            filler.push(b"ACGT"[(state >> 30) as usize] as char);
+        }
+        [filler.as_str(), SEQ].repeat(copies).concat()
+    }
+
+    #[test]
+    fn sequence_mask() {
+        let mut state = 5u32;
+        let seq = (0..5000)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                b"AAAAAAACGT"[(state >> 28) as usize % 10]
+            })
+            .collect::<Vec<u8>>();
+        for mask_size in [0, 1, 2, 3, 5, 8] {
+            let mut expected = Vec::new();
+            let mut i = 0;
+            while i < seq.len() {
+                let j = i + seq[i..].iter().take_while(|&&c| c == seq[i]).count();
+                if j - i > mask_size {
+                    expected.push((i, j));
+                }
+                i = j;
+            }
+            expected.push((seq.len(), seq.len()));
+            let mask = Sequence::new(seq.as_slice(), mask_size, 1)
+                .mask
+                .iter()
+                .map(|r| (r.start(), r.end()))
+                .collect::<Vec<_>>();
+            assert_eq!(mask, expected);
+        }
+
+        for period in [2, 3, 6] {
+            for mask_size in [4, 10, 20] {
+                let mut expected = Vec::new();
+                let mut i = 0;
+                while i + period < seq.len() {
+                    let n = (i..seq.len() - period)
+                        .take_while(|&k| seq[k] == seq[k + period])
+                        .count();
+                    if n > 0 && n + period > mask_size {
+                        expected.push((i, i + n + period));
+                    }
+                    i += n.max(1);
+                }
+                assert_eq!(tandem_repeats(&seq, period, mask_size), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn scan_mask_period() {
+        let seq = ["CA".repeat(60).as_str(), SEQ, "TTTAGG".repeat(30).as_str()].concat();
+        let scanner = ScannerBuilder::default().scan(seq.as_str());
+        assert_eq!(scanner.mask().len(), 0);
+        let crisprs = scanner.collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+
+        let mut builder = ScannerBuilder::default();
+        builder.mask_period(6);
+        let scanner = builder.scan(seq.as_str());
+        let mask = scanner
+            .mask()
+            .iter()
+            .map(|r| (r.start(), r.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(mask, [(0, 120), (120 + SEQ.len(), seq.len())]);
+        let masked = scanner.collect::<Vec<_>>();
+        assert_eq!(masked.len(), 1);
+        assert_eq!(masked[0].start(), crisprs[0].start());
+        assert_eq!(masked[0].end(), crisprs[0].end());
+    }
+
     #[test]
     fn scan_str() {
         let it = ScannerBuilder::default().scan(SEQ);
@@ -782,13 +1918,15 @@ mod tests {
         assert_eq!(crisprs[0].end(), 305);
 
         let region = crisprs[0].to_region();
-        assert!(region.starts_with(crisprs[0].repeat(0).as_ref()),);
-        assert!(region.ends_with(crisprs[0].repeat(4).as_ref()),);
+        assert!(region
+            .as_bytes()
+            .starts_with(crisprs[0].repeat(0).as_bytes()));
+        assert!(region.as_bytes().ends_with(crisprs[0].repeat(4).as_bytes()));
     }
 
     #[test]
     fn scan_rc() {
-        let it = ScannerBuilder::default().scan(Rc::from(SEQ));
+        let it = ScannerBuilder::default().scan(Rc::from(SEQ.as_bytes()));
         let crisprs = it.collect::<Vec<_>>();
         assert_eq!(crisprs.len(), 1);
 
@@ -798,8 +1936,659 @@ mod tests {
         assert_eq!(crisprs[0].end(), 305);
 
         let region = crisprs[0].to_region();
-        assert!(region.starts_with(crisprs[0].repeat(0).as_ref()),);
-        assert!(region.ends_with(crisprs[0].repeat(4).as_ref()),);
+        assert!(region
+            .as_bytes()
+            .starts_with(crisprs[0].repeat(0).as_bytes()));
+        assert!(region.as_bytes().ends_with(crisprs[0].repeat(4).as_bytes()));
+    }
+
+    #[test]
+    fn scan_bytes() {
+        let it = ScannerBuilder::default().scan(SEQ.as_bytes());
+        let crisprs = it.collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].repeat(0), "GTTTTAACTCCACACGGTACATTAGAAAC");
+        assert_eq!(crisprs[0].start(), 13);
+        assert_eq!(crisprs[0].end(), 305);
+
+        let it = ScannerBuilder::default().scan(Arc::<[u8]>::from(SEQ.as_bytes()));
+        let crisprs = it.collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].start(), 13);
+        assert_eq!(crisprs[0].end(), 305);
+        assert_eq!(
+            crisprs[0].repeat(0).to_str(),
+            Ok("GTTTTAACTCCACACGGTACATTAGAAAC")
+        );
+    }
+
+    #[test]
+    fn scan_strand() {
+        let crisprs = ScannerBuilder::default().scan(SEQ).collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].strand(), Strand::Plus);
+        assert!(crisprs[0].strand_confidence() > 0.5);
+        assert_eq!(
+            crisprs[0].oriented_repeat(0).as_ref(),
+            crisprs[0].repeat(0).as_bytes()
+        );
+
+        let rc = strand::reverse_complement(SEQ.as_bytes());
+        let crisprs_rc = ScannerBuilder::default()
+            .scan(rc.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs_rc.len(), 1);
+        assert_eq!(crisprs_rc[0].strand(), Strand::Minus);
+        assert_eq!(
+            crisprs_rc[0].strand_confidence(),
+            crisprs[0].strand_confidence()
+        );
+        for (x, y) in crisprs[0]
+            .oriented_repeats()
+            .zip(crisprs_rc[0].oriented_repeats())
+        {
+            assert_eq!(x, y);
+        }
+        for (x, y) in crisprs[0]
+            .oriented_spacers()
+            .zip(crisprs_rc[0].oriented_spacers())
+        {
+            assert_eq!(x, y);
+        }
+        assert_eq!(crisprs_rc[0].oriented_spacers().len(), 4);
+    }
+
+    #[test]
+    fn scan_consensus() {
+        let crisprs = ScannerBuilder::default().scan(SEQ).collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(
+            crisprs[0].consensus_repeat(),
+            b"GTTTTAACTCCACACGGTACATTAGAAAC"
+        );
+
+        let profile = crisprs[0].profile();
+        assert_eq!(profile.len(), 29);
+        assert!(profile.iter().all(|count| count.total() == 5));
+        assert_eq!(profile[0].frequency(b'G'), 1.0);
+    }
+
+    #[test]
+    fn scan_degeneracies() {
+        let crisprs = ScannerBuilder::default().scan(SEQ).collect::<Vec<_>>();
+        let degeneracies = crisprs[0].degeneracies();
+        assert_eq!(degeneracies.len(), 5);
+        assert!(degeneracies.iter().all(|d| !d.is_degenerate()));
+        assert!(degeneracies.iter().all(|d| !d.is_degenerate_trailer()));
+
+        let mut seq = SEQ.as_bytes().to_vec();
+        let last = crisprs[0].indices[4];
+        seq[last + 20] = b'G';
+        seq[last + 21] = b'C';
+        let crisprs = ScannerBuilder::default()
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].strand(), Strand::Plus);
+        let degeneracies = crisprs[0].degeneracies();
+        assert_eq!(degeneracies[4].index(), 4);
+        assert_eq!(degeneracies[4].mismatches(), &[20, 21]);
+        assert_eq!(degeneracies[4].distance(), 2);
+        assert!(degeneracies[4].is_degenerate_trailer());
+        assert!(!degeneracies[0].is_degenerate_trailer());
+    }
+
+    #[test]
+    fn scan_evidence() {
+        let crisprs = ScannerBuilder::default().scan(SEQ).collect::<Vec<_>>();
+        let evidence = crisprs[0].evidence();
+        assert_eq!(evidence.repeat_count(), 5);
+        assert_eq!(evidence.repeat_conservation(), 1.0);
+        assert!(evidence.spacer_diversity() > 0.3);
+        assert!(evidence.spacer_regularity() > 0.9);
+        assert!(evidence.score() > 0.6);
+        assert_eq!(evidence.level(), 4);
+
+        let mut seq = SEQ.as_bytes().to_vec();
+        let last = crisprs[0].indices[4];
+        for i in 0..6 {
+            seq[last + 10 + i] = b"TTTTTT"[i];
+        }
+        let degenerate = ScannerBuilder::default()
+            .scan(seq.as_slice())
+            .next()
+            .unwrap()
+            .evidence();
+        assert!(degenerate.repeat_conservation() < 1.0);
+        assert!(degenerate.score() < evidence.score());
+    }
+
+    #[test]
+    fn scan_significance() {
+        let seq = spaced(2);
+        let mut builder = ScannerBuilder::default();
+        builder.null_shuffles(20).null_seed(42);
+        let crisprs = builder.scan(seq.as_str()).collect::<Vec<_>>();
+        let significance = builder.significance(&seq, &crisprs).unwrap();
+        assert_eq!(significance.len(), 2);
+        for s in &significance {
+            assert_eq!(s.p_value(), 1.0 / 21.0);
+            assert_eq!(s.e_value(), 0.0);
+        }
+        assert_eq!(builder.significance(&seq, &crisprs).unwrap(), significance);
+
+        builder.null_shuffles(0);
+        let significance = builder.significance(&seq, &crisprs).unwrap();
+        assert_eq!(significance[0].p_value(), 1.0);
+        assert!(significance[0].e_value().is_nan());
+    }
+
+    #[test]
+    fn scan_indels() {
+        let crispr = ScannerBuilder::default().scan(SEQ).next().unwrap();
+        let mut seq = SEQ.as_bytes().to_vec();
+        seq.remove(crispr.indices[2] + 15);
+
+        let crisprs = ScannerBuilder::default()
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].repeat(2), "GTTTTAACTCCACACGTACATTAGAAACA");
+
+        let crisprs = ScannerBuilder::default()
+            .max_repeat_indels(2)
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].repeat(2), "GTTTTAACTCCACACGTACATTAGAAAC");
+        assert_eq!(crisprs[0].repeat(3), "GTTTTAACTCCACACGGTACATTAGAAAC");
+        assert_eq!(crisprs[0].spacer(1).start(), 107);
+        assert_eq!(crisprs[0].spacer(1).end(), 143);
+        assert_eq!(crisprs[0].spacer(2).start(), 171);
+        assert_eq!(crisprs[0].spacer(2).end(), 209);
+        assert_eq!(crisprs[0].spacers().len(), 4);
+        let degeneracies = crisprs[0].degeneracies();
+        assert_eq!(degeneracies[2].mismatches(), &[15]);
+        assert_eq!(degeneracies[2].distance(), 1);
+
+        let mut seq = SEQ.as_bytes().to_vec();
+        seq.insert(crispr.indices[4] + 15, b'A');
+        let crisprs = ScannerBuilder::default()
+            .max_repeat_indels(2)
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].len(), 5);
+        assert_eq!(crisprs[0].repeat(4), "GTTTTAACTCCACACAGGTACATTAGAAAC");
+        assert_eq!(crisprs[0].end(), crispr.end() + 1);
+        let degeneracies = crisprs[0].degeneracies();
+        assert_eq!(degeneracies[4].mismatches(), &[15]);
+        assert_eq!(degeneracies[4].distance(), 1);
+    }
+
+    #[test]
+    fn scan_circular() {
+        const PAD: &str = concat!(
+            "GCTAAAGACAATTACATAACATACACGTCAGCACGAAACTTGTTGGCCCAGTGTGAATCG",
+            "CTTAAGGGTTAAGTAAGTGTGATGCATACGCCTTTACTTGCTGTGTCCACCCCATCGGAC",
+        );
+        let seq = [SEQ, PAD].concat();
+        let crispr = ScannerBuilder::default().scan(seq.as_str()).next().unwrap();
+        let crisprs = ScannerBuilder::default()
+            .circular(true)
+            .scan(seq.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert!(!crisprs[0].is_wrapped());
+        assert_eq!(crisprs[0].start(), crispr.start());
+        assert_eq!(crisprs[0].end(), crispr.end());
+
+        let rotated = [&seq[150..], &seq[..150]].concat();
+        let crisprs = ScannerBuilder::default()
+            .scan(rotated.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 0);
+
+        let crisprs = ScannerBuilder::default()
+            .circular(true)
+            .scan(rotated.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert!(crisprs[0].is_wrapped());
+        assert_eq!(crisprs[0].len(), 5);
+        assert_eq!(crisprs[0].start(), crispr.start() + seq.len() - 150);
+        assert_eq!(crisprs[0].end(), crispr.end() - 150);
+        assert_eq!(crisprs[0].to_region().len(), crispr.to_region().len());
+        assert_eq!(crisprs[0].strand(), crispr.strand());
+        assert_eq!(crisprs[0].consensus_repeat(), crispr.consensus_repeat());
+        for (x, y) in crisprs[0].repeats().zip(crispr.repeats()) {
+            assert_eq!(x.to_bytes(), y.as_bytes());
+        }
+        for (x, y) in crisprs[0].spacers().zip(crispr.spacers()) {
+            assert_eq!(x, y.as_bytes());
+        }
+        assert!(crisprs[0].repeat(2).is_wrapped());
+        assert_eq!(crisprs[0].repeat(2).to_bytes(), crispr.repeat(2).as_bytes());
+        assert_eq!(
+            crisprs[0].to_region().to_bytes(),
+            crispr.to_region().as_bytes()
+        );
+        let repeat = crisprs[0].repeat(2);
+        let (head, tail) = repeat.as_slices();
+        assert_eq!([head, tail].concat(), crispr.repeat(2).as_bytes());
+
+        let mut builder = ScannerBuilder::default();
+        builder.circular(true);
+        let end = crisprs[0].end();
+        let start = crisprs[0].start();
+        let scan_range = |range| builder.scan_range(rotated.as_str(), range).unwrap();
+        assert_eq!(scan_range(end..start).count(), 0);
+        assert_eq!(scan_range(0..end).count(), 1);
+
+        let filler = &spaced(1)[..1000];
+        let middle = (start + end) / 2;
+        let padded = [&rotated[..middle], filler, &rotated[middle..]].concat();
+        builder.max_array_span(crispr.to_region().len() + 10);
+        let padded_crisprs = builder.scan(padded.as_str()).collect::<Vec<_>>();
+        assert_eq!(padded_crisprs.len(), 1);
+        assert!(padded_crisprs[0].is_wrapped());
+        assert_eq!(padded_crisprs[0].start(), start + filler.len());
+        assert_eq!(padded_crisprs[0].end(), end);
+
+        #[cfg(feature = "rayon")]
+        {
+            let par_crisprs = ScannerBuilder::default()
+                .circular(true)
+                .par_scan(rotated.as_str())
+                .unwrap();
+            assert_eq!(par_crisprs.len(), 1);
+            assert_eq!(par_crisprs[0].start(), crisprs[0].start());
+            assert_eq!(par_crisprs[0].end(), crisprs[0].end());
+        }
+    }
+
+    #[test]
+    fn scan_ambiguity() {
+        let crispr = ScannerBuilder::default().scan(SEQ).next().unwrap();
+
+        let mut seq = SEQ.as_bytes().to_vec();
+        let gap = crispr.spacer(2).start() + 10;
+        seq[gap..gap + 5].copy_from_slice(b"NNNNN");
+        let crisprs = ScannerBuilder::default()
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].len(), 5);
+        let crisprs = ScannerBuilder::default()
+            .ambiguity(Ambiguity::Break)
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].len(), 3);
+        assert!(crisprs[0].end() <= gap);
+
+        let err = ScannerBuilder::default()
+            .ambiguity(Ambiguity::Reject)
+            .try_scan(seq.as_slice())
+            .err();
+        assert_eq!(
+            err,
+            Some(Error::AmbiguousBase {
+                position: gap,
+                base: b'N'
+            })
+        );
+        let err = ScannerBuilder::default()
+            .ambiguity(Ambiguity::Reject)
+            .scan_range(seq.as_slice(), 0..10)
+            .err();
+        assert!(matches!(err, Some(Error::AmbiguousBase { .. })));
+        let results = ScannerBuilder::default()
+            .ambiguity(Ambiguity::Reject)
+            .scan_many([
+                (0, Arc::<[u8]>::from(seq.as_slice())),
+                (1, Arc::<[u8]>::from(SEQ.as_bytes())),
+            ])
+            .map(|(_, result)| result.map(|crisprs| crisprs.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            [
+                Err(Error::AmbiguousBase {
+                    position: gap,
+                    base: b'N'
+                }),
+                Ok(1)
+            ]
+        );
+        #[cfg(feature = "rayon")]
+        {
+            let err = ScannerBuilder::default()
+                .ambiguity(Ambiguity::Reject)
+                .par_scan(seq.as_slice())
+                .err();
+            assert!(matches!(err, Some(Error::AmbiguousBase { .. })));
+        }
+
+        let mut seq = SEQ.as_bytes().to_vec();
+        let last = crispr.repeat(4).start();
+        seq[last..last + 10].copy_from_slice(b"NNNNNNNNNN");
+        let crisprs = ScannerBuilder::default()
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].len(), 4);
+        let crisprs = ScannerBuilder::default()
+            .ambiguity(Ambiguity::Wildcard)
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].len(), 5);
+        assert_eq!(crisprs[0].end(), crispr.end());
+    }
+
+    #[test]
+    fn scan_ambiguity_reject() {
+        let mut seq = SEQ.as_bytes().to_vec();
+        seq[100] = b'N';
+        let mut builder = ScannerBuilder::default();
+        builder.ambiguity(Ambiguity::Reject);
+        assert_eq!(builder.scan(seq.as_slice()).count(), 0);
+        assert_eq!(
+            builder.clone().circular(true).scan(seq.as_slice()).count(),
+            0
+        );
+        let err = builder
+            .significance(seq.as_slice(), &[] as &[Crispr<&[u8]>])
+            .err();
+        assert_eq!(
+            err,
+            Some(Error::AmbiguousBase {
+                position: 100,
+                base: b'N'
+            })
+        );
+    }
+
+    #[test]
+    fn scan_soft_masked() {
+        let crispr = ScannerBuilder::default().scan(SEQ).next().unwrap();
+        let mut seq = SEQ.as_bytes().to_vec();
+        for k in 1..4 {
+            let repeat = crispr.repeat(k);
+            seq[repeat.start()..repeat.end()].make_ascii_lowercase();
+        }
+
+        let crisprs = ScannerBuilder::default()
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert!(crisprs.iter().all(|c| c.len() < 5));
+
+        let crisprs = ScannerBuilder::default()
+            .ignore_case(true)
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].len(), 5);
+        assert_eq!(crisprs[0].start(), crispr.start());
+        assert_eq!(crisprs[0].end(), crispr.end());
+        assert_eq!(
+            crisprs[0].repeat(2).as_bytes(),
+            b"gttttaactccacacggtacattagaaac"
+        );
+        assert_ne!(crisprs[0].repeat(2), "GTTTTAACTCCACACGGTACATTAGAAAC");
+
+        let crisprs = ScannerBuilder::default()
+            .ignore_case(true)
+            .normalize_case(true)
+            .scan(seq.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(
+            crisprs[0].repeat(2).as_bytes(),
+            b"GTTTTAACTCCACACGGTACATTAGAAAC"
+        );
+        assert_eq!(
+            crisprs[0].repeat(2).to_bytes(),
+            &b"GTTTTAACTCCACACGGTACATTAGAAAC"[..]
+        );
+        assert_eq!(crisprs[0].repeat(2), "GTTTTAACTCCACACGGTACATTAGAAAC");
+        assert_eq!(seq[crisprs[0].repeat(2).start()], b'g');
+
+        let seq = String::from_utf8(seq).unwrap();
+        let crisprs = ScannerBuilder::default()
+            .ignore_case(true)
+            .normalize_case(true)
+            .scan(seq.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            crisprs[0].repeat(2).as_str(),
+            "GTTTTAACTCCACACGGTACATTAGAAAC"
+        );
+        assert_eq!(crisprs[0].repeat(1).as_str(), crispr.repeat(1).as_str());
+        assert_eq!(crisprs[0].repeat(2).to_bytes(), crispr.repeat(2).as_bytes());
+        assert_eq!(crisprs[0].oriented_repeat(2), crispr.oriented_repeat(2));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_scan() {
+        let seq = spaced(100);
+        let mut builder = ScannerBuilder::default();
+        for mask_period in [1, 4] {
+            builder.mask_period(mask_period);
+            let expected = builder
+                .scan(seq.as_str())
+                .map(|c| (c.start(), c.end()))
+                .collect::<Vec<_>>();
+            let actual = builder
+                .par_scan(seq.as_str())
+                .unwrap()
+                .iter()
+                .map(|c| (c.start(), c.end()))
+                .collect::<Vec<_>>();
+            assert_eq!(expected.len(), 100);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn scan_many() {
+        let rc = strand::reverse_complement(SEQ.as_bytes());
+        let sequences = (0..50).map(|i| match i % 3 {
+            0 => (i, Arc::<[u8]>::from(SEQ.as_bytes())),
+            1 => (i, Arc::<[u8]>::from(&b""[..])),
+            _ => (i, Arc::<[u8]>::from(rc.as_slice())),
+        });
+        let results = ScannerBuilder::default()
+            .threads(3)
+            .scan_many(sequences)
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 50);
+        for (i, (id, crisprs)) in results.iter().enumerate() {
+            assert_eq!(*id, i);
+            let crisprs = crisprs.as_ref().unwrap();
+            match i % 3 {
+                1 => assert_eq!(crisprs.len(), 0),
+                0 => {
+                    assert_eq!(crisprs.len(), 1);
+                    assert_eq!(crisprs[0].strand(), Strand::Plus);
+                }
+                _ => {
+                    assert_eq!(crisprs.len(), 1);
+                    assert_eq!(crisprs[0].strand(), Strand::Minus);
+                }
+            }
+        }
+
+        let mut it = ScannerBuilder::default().scan_many([("a", SEQ), ("b", SEQ)]);
+        assert_eq!(
+            it.next().map(|(id, c)| (id, c.unwrap().len())),
+            Some(("a", 1))
+        );
+        drop(it);
+
+        let results = ScannerBuilder::default()
+            .min_repeat_length(50)
+            .max_repeat_length(10)
+            .scan_many([("a", SEQ), ("b", SEQ)])
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        for (_, result) in results {
+            assert_eq!(
+                result.err(),
+                Some(ParameterError::RepeatLength { min: 50, max: 10 }.into())
+            );
+        }
+    }
+
+    #[test]
+    fn scan_many_drop() {
+        /// A sequence recording whether it was scanned.
+        #[derive(Clone)]
+        struct Tracked(Arc<[u8]>, Arc<AtomicBool>);
+
+        impl AsRef<[u8]> for Tracked {
+            fn as_ref(&self) -> &[u8] {
+                self.1.store(true, Ordering::Relaxed);
+                &self.0
+            }
+        }
+
+        let seq = Arc::<[u8]>::from(spaced(200).as_bytes());
+        let flags = (0..20)
+            .map(|_| Arc::new(AtomicBool::new(false)))
+            .collect::<Vec<_>>();
+        let sequences = flags
+            .iter()
+            .enumerate()
+            .map(|(i, flag)| (i, Tracked(seq.clone(), flag.clone())));
+
+        // dropping the iterator must not scan the queued sequences
+        let mut it = ScannerBuilder::default().threads(1).scan_many(sequences);
+        assert_eq!(it.next().map(|(id, _)| id), Some(0));
+        drop(it);
+        let scanned = flags.iter().filter(|f| f.load(Ordering::Relaxed)).count();
+        assert!(scanned <= 3, "{} sequences scanned", scanned);
+    }
+
+    #[test]
+    fn scan_reader() {
+        let seq = spaced(5);
+        let lines = seq
+            .as_bytes()
+            .chunks(60)
+            .flat_map(|line| line.iter().chain(b"\n"))
+            .cloned()
+            .collect::<Vec<u8>>();
+
+        let mut builder = ScannerBuilder::default();
+        builder.max_array_span(500);
+        let expected = builder.scan(seq.as_str()).collect::<Vec<_>>();
+        let actual = builder
+            .scan_reader(std::io::Cursor::new(lines))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(&expected) {
+            assert_eq!(a.start(), e.start());
+            assert_eq!(a.end(), e.end());
+            assert_eq!(a.strand(), e.strand());
+            for (ra, re) in a.repeats().zip(e.repeats()) {
+                assert_eq!(ra.start(), re.start());
+                assert_eq!(ra, re.as_bytes());
+            }
+            for (sa, se) in a.spacers().zip(e.spacers()) {
+                assert_eq!(sa.start(), se.start());
+                assert_eq!(sa.end(), se.end());
+            }
+        }
+
+        let mut builder = ScannerBuilder::default();
+        builder.ambiguity(Ambiguity::Reject);
+        let mut it = builder.scan_reader(&b"ACGT\nACNT\n"[..]);
+        match it.next() {
+            Some(Err(e)) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+            _ => panic!("expected an error"),
+        }
+        assert!(it.next().is_none());
+
+        let mut builder = ScannerBuilder::default();
+        builder.search_window_length(0);
+        let mut it = builder.scan_reader(seq.as_bytes());
+        match it.next() {
+            Some(Err(e)) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput),
+            _ => panic!("expected an error"),
+        }
+        assert!(it.next().is_none());
+
+        let mut builder = ScannerBuilder::default();
+        builder.circular(true);
+        let mut it = builder.scan_reader(seq.as_bytes());
+        match it.next() {
+            Some(Err(e)) => {
+                assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput);
+                let error = e.into_inner().unwrap().downcast::<Error>().unwrap();
+                assert_eq!(*error, Error::CircularStream);
+            }
+            _ => panic!("expected an error"),
+        }
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn scan_range() {
+        let seq = spaced(5);
+        let builder = ScannerBuilder::default();
+        let crisprs = builder.scan(seq.as_str()).collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 5);
+
+        let start = crisprs[2].start();
+        let found = builder
+            .scan_range(seq.as_str(), start - 10..start + 10)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].start(), start);
+        assert_eq!(found[0].end(), crisprs[2].end());
+
+        // a seed inside the array is extended back to its first repeat
+        let middle = crisprs[2].repeat(1).start();
+        let found = builder
+            .scan_range(seq.as_str(), middle..middle + 1)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].start(), start);
+        assert_eq!(found[0].end(), crisprs[2].end());
+
+        let found = builder
+            .scan_range(seq.as_str(), 0..crisprs[0].start() - 500)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(found.len(), 0);
+
+        let err = builder
+            .scan_range(seq.as_str(), Range { start: 10, end: 5 })
+            .err();
+        assert_eq!(err, Some(Error::ScanRange { start: 10, end: 5 }));
+        let err = ScannerBuilder::default()
+            .min_repeat_length(50)
+            .max_repeat_length(10)
+            .scan_range(seq.as_str(), 0..10)
+            .err();
+        assert_eq!(
+            err,
+            Some(ParameterError::RepeatLength { min: 50, max: 10 }.into())
+        );
+
+        let mut scanner = builder.scan(seq.as_str());
+        scanner.seek(crisprs[3].start());
+        assert_eq!(scanner.next().map(|c| c.start()), Some(crisprs[3].start()));
+        assert_eq!(scanner.next().map(|c| c.start()), Some(crisprs[4].start()));
+        assert!(scanner.next().is_none());
+        scanner.seek(0);
+        assert_eq!(scanner.next().map(|c| c.start()), Some(crisprs[0].start()));
     }
 
     #[test]
@@ -826,9 +2615,144 @@ mod tests {
         assert_eq!(crisprs.len(), 0);
     }
 
+    #[test]
+    fn try_scan_invalid() {
+        let err = ScannerBuilder::default()
+            .min_repeat_length(40)
+            .max_repeat_length(10)
+            .try_scan(SEQ)
+            .err();
+        assert_eq!(
+            err,
+            Some(ParameterError::RepeatLength { min: 40, max: 10 }.into())
+        );
+
+        let err = ScannerBuilder::default()
+            .min_spacer_length(40)
+            .max_spacer_length(10)
+            .try_scan(SEQ)
+            .err();
+        assert_eq!(
+            err,
+            Some(ParameterError::SpacerLength { min: 40, max: 10 }.into())
+        );
+
+        let err = ScannerBuilder::default()
+            .min_repeat_count(1)
+            .try_scan(SEQ)
+            .err();
+        assert_eq!(err, Some(ParameterError::MinRepeatCount(1).into()));
+
+        let err = ScannerBuilder::default()
+            .search_window_length(0)
+            .try_scan(SEQ)
+            .err();
+        assert_eq!(err, Some(ParameterError::SearchWindowLength(0).into()));
+
+        let err = ScannerBuilder::default()
+            .flank_confidence(1.5)
+            .try_scan(SEQ)
+            .err();
+        assert_eq!(err, Some(ParameterError::FlankConfidence(1.5).into()));
+
+        let err = ScannerBuilder::default()
+            .right_scan_range(200)
+            .build(SEQ)
+            .err();
+        assert_eq!(err, Some(ParameterError::RightScanRange(200).into()));
+
+        let err = ScannerBuilder::default()
+            .flank_scan_range(200)
+            .build(SEQ)
+            .err();
+        assert_eq!(err, Some(ParameterError::FlankScanRange(200).into()));
+
+        let err = ScannerBuilder::default()
+            .max_repeat_indels(23)
+            .build(SEQ)
+            .err();
+        assert_eq!(err, Some(ParameterError::MaxRepeatIndels(23).into()));
+
+        let err = ScannerBuilder::default().mask_size(0).build(SEQ).err();
+        assert_eq!(err, Some(ParameterError::MaskSize(0).into()));
+
+        let err = ScannerBuilder::default()
+            .max_array_span(50)
+            .build(SEQ)
+            .err();
+        assert_eq!(err, Some(ParameterError::MaxArraySpan(50).into()));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_scan_invalid() {
+        let err = ScannerBuilder::default()
+            .min_repeat_length(50)
+            .max_repeat_length(10)
+            .par_scan(SEQ)
+            .err();
+        assert_eq!(
+            err,
+            Some(ParameterError::RepeatLength { min: 50, max: 10 }.into())
+        );
+        let err = ScannerBuilder::default()
+            .search_window_length(0)
+            .par_scan(SEQ)
+            .err();
+        assert_eq!(err, Some(ParameterError::SearchWindowLength(0).into()));
+    }
+
+    #[test]
+    fn try_scan_valid() {
+        let crisprs = ScannerBuilder::default()
+            .try_scan(SEQ)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+
+        let crisprs = ScannerBuilder::default()
+            .build(SEQ)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+    }
+
+    #[test]
+    fn scan_invalid_no_panic() {
+        let it = ScannerBuilder::default().min_repeat_count(0).scan(SEQ);
+        assert_eq!(it.count(), 1);
+
+        let it = ScannerBuilder::default().search_window_length(0).scan(SEQ);
+        let _ = it.count();
+    }
+
+    #[test]
+    fn scan_heuristics() {
+        let crisprs = ScannerBuilder::default()
+            .repeat_threshold(0.75)
+            .spacer_to_spacer_max_similarity(0.62)
+            .flank_scan_range(30)
+            .flank_confidence(0.7)
+            .scan(SEQ)
+            .collect::<Vec<_>>();
+        assert_eq!(crisprs.len(), 1);
+        assert_eq!(crisprs[0].start(), 13);
+        assert_eq!(crisprs[0].end(), 305);
+
+        let it = ScannerBuilder::default()
+            .spacer_to_spacer_max_similarity(0.1)
+            .scan(SEQ);
+        assert_eq!(it.count(), 0);
+
+        let it = ScannerBuilder::default()
+            .spacer_to_spacer_length_diff(0)
+            .scan(SEQ);
+        assert_eq!(it.count(), 0);
+    }
+
     #[test]
     fn scan_unicode() {
-        const UNICODE: &'static str = concat!(
+        const UNICODE: &str = concat!(
             "AAAAAAAGAFCACATTGACGCGGGGGGGGCATACCAAACATAATTGACcCGGACACGCCAAGGCT",
             "CACGTTAACAAAAGACACGACGCGGGACAATAGGATAAACATAATTGACTAAACGTGGGAACACG",
             "CGGGCATACCAAACATAATTGACcCGGATTGACGCGGGACAATAGGATAAACATAATTGACCACC",
@@ -842,7 +2766,7 @@ mod tests {
         let crisprs = it.collect::<Vec<_>>();
         assert_eq!(crisprs.len(), 0);
 
-        const UNICODE2: &'static str = concat!(
+        const UNICODE2: &str = concat!(
             "GAAJJJJGssGAGAGGTATAACCAbAACCGTTGTGTJJJJJJJJJJJJJJJJJJJGGTATAACCA",
             "bAACCGTTGTGT@AGGAGAGGTATAACCAbAACCGTTGTGT@GGTJJJJJJGGTATAACCAbAAC",
             "CGTATAAGAGGTATAACCAbAACCGTTGTGT@GGTATAGATCTAATGG?AATGAAGGCAATAAGG",
@@ -853,7 +2777,7 @@ mod tests {
         let crisprs2 = it2.collect::<Vec<_>>();
         assert_eq!(crisprs2.len(), 0);
 
-        const UNICODE3: &'static str = concat!(
+        const UNICODE3: &str = concat!(
             "GAAJJJJJJJJJJJJJJJJJJJJJJJGGTATAACCAbAACCGTTGTGT@AGGAGAGGTATAACCAbAACC",
             "GTTGTGT@GGTJJJJJJGGTATAACCAbAACCGTATAAGAGGTATAACCAAGAGGTATAACCAbAACCGT",
             "TGTGGAAJJJJJJJJJJJJJJJJJCCGTTGTTGTGT@AGGAGAGGTATAACCAbAACCGTTGTGT@GGTJ",
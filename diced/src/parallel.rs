@@ -0,0 +1,110 @@
+use rayon::prelude::*;
+
+use super::Crispr;
+use super::Scanner;
+
+/// The number of seeds evaluated by a single task.
+const CHUNK_SEEDS: usize = 1 << 10;
+
+/// The number of tasks per thread in each batch of chunks.
+const CHUNKS_PER_THREAD: usize = 4;
+
+/// The result of the serial search over a chunk of seed positions.
+struct Chunk<S> {
+    /// The end of the seed positions of the chunk.
+    end: usize,
+    /// The positions of the seeds evaluated in the chunk, in order.
+    evaluated: Vec<usize>,
+    /// The CRISPR regions found in the chunk, with their seed position.
+    found: Vec<(usize, Crispr<S>)>,
+    /// The search position after the chunk.
+    j: usize,
+    /// The mask cursor after the chunk.
+    mask_index: usize,
+}
+
+impl<S: AsRef<[u8]> + Clone + Send + Sync> Scanner<S> {
+    /// Collect the remaining CRISPR regions using several threads.
+    ///
+    /// The serial scanner jumps to the end of every region it finds, and
+    /// otherwise evaluates a seed every few positions, so the seeds it
+    /// evaluates depend on the previous regions. The sequence is split
+    /// into batches of chunks starting at the current position, and the
+    /// serial search is run on every chunk in parallel. The serial
+    /// trajectory is then replayed from the end of each chunk until it
+    /// reaches a seed evaluated by the next chunk, from where both
+    /// searches are identical, so the regions found by the next chunk
+    /// from that seed onwards are kept and the others are discarded.
+    pub(crate) fn collect_parallel(mut self) -> Vec<Crispr<S>> {
+        if self.parameters.circular && !self.origin_probed {
+            self._probe_origin();
+        }
+
+        let end = self._search_end();
+        let chunk_size = self._skips() * CHUNK_SEEDS;
+        let batch_size = chunk_size * CHUNKS_PER_THREAD * rayon::current_num_threads();
+
+        let mut crisprs = Vec::new();
+        let (mut j, mut mask_index) = (self.j, self.mask_index);
+        while j < end {
+            let batch_end = end.min(j + batch_size);
+            for chunk in self._search_chunks(j, batch_end, chunk_size) {
+                // replay the serial search one seed at a time until it
+                // joins the search of the chunk
+                while j < chunk.end && chunk.evaluated.binary_search(&j).is_err() {
+                    let step_end = chunk.end.min(j + 1);
+                    crisprs.extend(self._next_linear_from(
+                        &mut j,
+                        &mut mask_index,
+                        step_end,
+                        Self::_seed,
+                    ));
+                }
+                if j < chunk.end {
+                    let found = chunk.found.into_iter();
+                    crisprs.extend(found.filter(|(i, _)| *i >= j).map(|(_, c)| c));
+                    (j, mask_index) = (chunk.j, chunk.mask_index);
+                }
+            }
+        }
+
+        (self.j, self.mask_index) = (j, mask_index);
+        crisprs.extend(self.origin.take());
+        crisprs
+    }
+
+    /// Run the serial search on chunks of the seed positions in `start..end`.
+    ///
+    /// Chunks start every `chunk_size` positions, which is a multiple of
+    /// the seed spacing, so that they follow the same grid as a search
+    /// started at `start`.
+    fn _search_chunks(&self, start: usize, end: usize, chunk_size: usize) -> Vec<Chunk<S>> {
+        (start..end)
+            .into_par_iter()
+            .step_by(chunk_size)
+            .map(|chunk_start| {
+                let chunk_end = end.min(chunk_start + chunk_size);
+                let mask = &self.sequence.mask;
+                let mut mask_index = mask.partition_point(|m| m.end() < chunk_start);
+                let mut j = chunk_start;
+                let mut evaluated = Vec::new();
+                let mut found = Vec::new();
+                while let Some(crispr) =
+                    self._next_linear_from(&mut j, &mut mask_index, chunk_end, |this, j| {
+                        evaluated.push(j);
+                        this._seed(j)
+                    })
+                {
+                    found.push((evaluated[evaluated.len() - 1], crispr));
+                }
+                Chunk {
+                    end: chunk_end,
+                    evaluated,
+                    found,
+                    j,
+                    mask_index,
+                }
+            })
+            .collect()
+    }
+}
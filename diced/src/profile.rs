@@ -0,0 +1,191 @@
+use std::ops::Deref;
+
+/// The nucleotide counts at a single position.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DnaCount {
+    a: usize,
+    t: usize,
+    c: usize,
+    g: usize,
+}
+
+impl DnaCount {
+    /// Create a new counter with all counts set to zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset all counts to zero.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.a = 0;
+        self.c = 0;
+        self.t = 0;
+        self.g = 0;
+    }
+
+    /// Count a single nucleotide.
+    ///
+    /// Counting is case-insensitive, and symbols other than `A`, `C`, `G`
+    /// and `T` are ignored.
+    #[inline]
+    pub fn count(&mut self, c: u8) {
+        match c {
+            b'a' | b'A' => self.a += 1,
+            b'c' | b'C' => self.c += 1,
+            b'g' | b'G' => self.g += 1,
+            b't' | b'T' => self.t += 1,
+            _ => (),
+        }
+    }
+
+    /// Get the number of `A` counted.
+    #[inline]
+    pub fn a(&self) -> usize {
+        self.a
+    }
+
+    /// Get the number of `C` counted.
+    #[inline]
+    pub fn c(&self) -> usize {
+        self.c
+    }
+
+    /// Get the number of `G` counted.
+    #[inline]
+    pub fn g(&self) -> usize {
+        self.g
+    }
+
+    /// Get the number of `T` counted.
+    #[inline]
+    pub fn t(&self) -> usize {
+        self.t
+    }
+
+    /// Get the total number of nucleotides counted.
+    #[inline]
+    pub fn total(&self) -> usize {
+        self.a + self.c + self.g + self.t
+    }
+
+    /// Get the count of the most frequent nucleotide.
+    #[inline]
+    pub fn max(&self) -> usize {
+        self.a.max(self.c).max(self.t).max(self.g)
+    }
+
+    /// Get the most frequent nucleotide, if any was counted.
+    ///
+    /// Ties are broken in `A`, `C`, `G`, `T` order.
+    #[inline]
+    pub fn argmax(&self) -> Option<u8> {
+        let max = self.max();
+        if max == 0 {
+            None
+        } else if self.a == max {
+            Some(b'A')
+        } else if self.c == max {
+            Some(b'C')
+        } else if self.g == max {
+            Some(b'G')
+        } else {
+            Some(b'T')
+        }
+    }
+
+    /// Get the frequency of a nucleotide among the counted ones.
+    ///
+    /// Returns `0.0` if nothing was counted, or if `c` is not one of
+    /// `A`, `C`, `G` or `T`.
+    pub fn frequency(&self, c: u8) -> f32 {
+        let n = match c {
+            b'a' | b'A' => self.a,
+            b'c' | b'C' => self.c,
+            b'g' | b'G' => self.g,
+            b't' | b'T' => self.t,
+            _ => 0,
+        };
+        match self.total() {
+            0 => 0.0,
+            total => n as f32 / total as f32,
+        }
+    }
+}
+
+/// The per-position nucleotide counts across the repeats of a CRISPR.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Profile {
+    counts: Vec<DnaCount>,
+}
+
+impl Profile {
+    /// Build a profile from a collection of equally-sized sequences.
+    ///
+    /// Positions beyond the end of shorter sequences are not counted.
+    pub fn new<I, T>(length: usize, sequences: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let mut counts = vec![DnaCount::new(); length];
+        for sequence in sequences {
+            for (count, &c) in counts.iter_mut().zip(sequence.as_ref()) {
+                count.count(c);
+            }
+        }
+        Self { counts }
+    }
+
+    /// Get the consensus sequence of the profile.
+    ///
+    /// Each position is the most frequent nucleotide, or `N` if no
+    /// nucleotide was counted at that position.
+    pub fn consensus(&self) -> Vec<u8> {
+        self.counts
+            .iter()
+            .map(|count| count.argmax().unwrap_or(b'N'))
+            .collect()
+    }
+}
+
+impl Deref for Profile {
+    type Target = [DnaCount];
+    fn deref(&self) -> &Self::Target {
+        self.counts.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count() {
+        let mut count = DnaCount::new();
+        for &c in b"AacGtN-" {
+            count.count(c);
+        }
+        assert_eq!(count.a(), 2);
+        assert_eq!(count.c(), 1);
+        assert_eq!(count.g(), 1);
+        assert_eq!(count.t(), 1);
+        assert_eq!(count.total(), 5);
+        assert_eq!(count.argmax(), Some(b'A'));
+        assert_eq!(count.frequency(b'a'), 0.4);
+        assert_eq!(count.frequency(b'N'), 0.0);
+        count.clear();
+        assert_eq!(count.argmax(), None);
+        assert_eq!(count.frequency(b'A'), 0.0);
+    }
+
+    #[test]
+    fn consensus() {
+        let profile = Profile::new(4, [&b"ACGT"[..], b"ACGA", b"TCG"]);
+        assert_eq!(profile.len(), 4);
+        assert_eq!(profile[3].a(), 1);
+        assert_eq!(profile[3].t(), 1);
+        assert_eq!(profile.consensus(), b"ACGA");
+        assert_eq!(Profile::new(2, Vec::<&[u8]>::new()).consensus(), b"NN");
+    }
+}
@@ -1,5 +1,6 @@
-use std::ops::Deref;
+use std::borrow::Cow;
 use std::ops::Range;
+use std::str::Utf8Error;
 
 use super::Crispr;
 
@@ -10,11 +11,24 @@ pub enum RegionType {
 }
 
 /// A sequence region.
+///
+/// Regions of a circular sequence may cross the origin, in which case
+/// their start coordinate is greater than their end coordinate. Their
+/// sequence is not contiguous, so [`Region::as_bytes`] and
+/// [`Region::as_str`] panic for such a region: use [`Region::as_slices`]
+/// or [`Region::to_bytes`] to access the sequence of any region.
+///
+/// Regions obtained from a scanner configured to normalize the case
+/// (see [`ScannerBuilder::normalize_case`](crate::ScannerBuilder::normalize_case))
+/// hold an uppercase copy of their sequence if it contains lowercase
+/// nucleotides, which is returned by all the accessors.
 #[derive(Debug)]
 pub struct Region<S> {
     sequence: S,
     start: usize,
     end: usize,
+    offset: usize,
+    uppercase: Option<Box<[u8]>>,
 }
 
 impl<S> Region<S> {
@@ -24,61 +38,145 @@ impl<S> Region<S> {
             sequence,
             start,
             end,
+            offset: 0,
+            uppercase: None,
         }
     }
 
+    /// Set the position of the sequence in a larger sequence.
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.start == self.end
+    pub(crate) fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
     }
 
     #[inline]
-    pub fn len(&self) -> usize {
-        self.end - self.start
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
     }
 
     #[inline]
     pub fn start(&self) -> usize {
-        self.start
+        self.offset + self.start
     }
 
     #[inline]
     pub fn end(&self) -> usize {
-        self.end
+        self.offset + self.end
+    }
+
+    /// Check whether the region crosses the origin of a circular sequence.
+    #[inline]
+    pub fn is_wrapped(&self) -> bool {
+        self.start > self.end
     }
 }
 
-impl<S: AsRef<str>> Region<S> {
+impl<S: AsRef<[u8]>> Region<S> {
+    /// Make the region return its sequence in uppercase.
+    ///
+    /// The sequence is only copied if it contains lowercase nucleotides.
+    pub(crate) fn with_uppercase(mut self, uppercase: bool) -> Self {
+        let (head, tail) = self.as_slices();
+        if uppercase && head.iter().chain(tail).any(u8::is_ascii_lowercase) {
+            let bytes = [head, tail].concat().to_ascii_uppercase();
+            self.uppercase = Some(bytes.into_boxed_slice());
+        }
+        self
+    }
+
     #[inline]
-    pub fn as_str(&self) -> &str {
-        self.as_ref()
+    pub fn len(&self) -> usize {
+        if self.is_wrapped() {
+            self.sequence.as_ref().len() - self.start + self.end
+        } else {
+            self.end - self.start
+        }
     }
 
+    /// Get the sequence region as a byte slice.
+    ///
+    /// # Panic
+    /// Panics if the region crosses the origin of a circular sequence.
     #[inline]
     pub fn as_bytes(&self) -> &[u8] {
-        self.as_ref().as_bytes()
+        let bytes = &self.sequence.as_ref()[self.start..self.end];
+        self.uppercase.as_deref().unwrap_or(bytes)
+    }
+
+    /// Get the sequence region as two byte slices.
+    ///
+    /// The second slice is only non-empty if the region crosses the
+    /// origin of a circular sequence, in which case it contains the part
+    /// of the region after the origin.
+    #[inline]
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        let sequence = self.sequence.as_ref();
+        let (head, tail) = if self.is_wrapped() {
+            (&sequence[self.start..], &sequence[..self.end])
+        } else {
+            (&sequence[self.start..self.end], &[][..])
+        };
+        match self.uppercase.as_deref() {
+            Some(bytes) => bytes.split_at(head.len()),
+            None => (head, tail),
+        }
     }
-}
 
-impl<S: AsRef<str>> AsRef<str> for Region<S> {
+    /// Get the sequence region as contiguous bytes.
+    ///
+    /// This only copies the sequence if the region crosses the origin of
+    /// a circular sequence.
+    pub fn to_bytes(&self) -> Cow<'_, [u8]> {
+        match self.as_slices() {
+            (head, []) => Cow::Borrowed(head),
+            (head, tail) => Cow::Owned([head, tail].concat()),
+        }
+    }
+
+    /// Get the sequence region as a string, if it contains valid UTF-8.
+    ///
+    /// # Panic
+    /// Panics if the region crosses the origin of a circular sequence.
     #[inline]
-    fn as_ref(&self) -> &str {
-        &self.sequence.as_ref()[self.start..self.end]
+    pub fn to_str(&self) -> Result<&str, Utf8Error> {
+        std::str::from_utf8(self.as_bytes())
     }
 }
 
-impl<S: AsRef<str>> Deref for Region<S> {
-    type Target = str;
+impl<S: AsRef<str>> Region<S> {
+    /// Get the sequence region as a string slice.
+    ///
+    /// # Panic
+    /// Panics if the region crosses the origin of a circular sequence, or
+    /// if the region boundaries do not lie on UTF-8 character boundaries,
+    /// which can only happen with non-ASCII sequences.
     #[inline]
-    fn deref(&self) -> &Self::Target {
-        self.as_ref()
+    pub fn as_str(&self) -> &str {
+        let s = &self.sequence.as_ref()[self.start..self.end];
+        match self.uppercase.as_deref() {
+            Some(bytes) => std::str::from_utf8(bytes).expect("uppercasing preserves UTF-8"),
+            None => s,
+        }
     }
 }
 
-impl<S: AsRef<str>> PartialEq<&str> for Region<S> {
+impl<S: AsRef<[u8]>> PartialEq<&str> for Region<S> {
     #[inline]
     fn eq(&self, other: &&str) -> bool {
-        self.as_ref() == *other
+        self == &other.as_bytes()
+    }
+}
+
+impl<S: AsRef<[u8]>> PartialEq<&[u8]> for Region<S> {
+    #[inline]
+    fn eq(&self, other: &&[u8]) -> bool {
+        let (head, tail) = self.as_slices();
+        if other.len() != head.len() + tail.len() {
+            return false;
+        }
+        let (x, y) = other.split_at(head.len());
+        head == x && tail == y
     }
 }
 
@@ -90,10 +188,14 @@ pub struct Regions<'c, S> {
     ty: RegionType,
 }
 
-impl<'c, S: AsRef<str> + Clone> Regions<'c, S> {
+impl<'c, S: AsRef<[u8]> + Clone> Regions<'c, S> {
     pub(crate) fn new(crispr: &'c Crispr<S>, ty: RegionType) -> Self {
+        let n = match ty {
+            RegionType::Repeat => crispr.len(),
+            RegionType::Spacer => crispr.len().saturating_sub(1),
+        };
         Self {
-            indices: 0..crispr.len(),
+            indices: 0..n,
             crispr,
             ty,
         }
@@ -108,7 +210,7 @@ impl<'c, S: AsRef<str> + Clone> Regions<'c, S> {
     }
 }
 
-impl<'c, S: AsRef<str> + Clone> Iterator for Regions<'c, S> {
+impl<'c, S: AsRef<[u8]> + Clone> Iterator for Regions<'c, S> {
     type Item = Region<S>;
 
     #[inline]
@@ -122,7 +224,7 @@ impl<'c, S: AsRef<str> + Clone> Iterator for Regions<'c, S> {
     }
 }
 
-impl<'c, S: AsRef<str> + Clone> DoubleEndedIterator for Regions<'c, S> {
+impl<'c, S: AsRef<[u8]> + Clone> DoubleEndedIterator for Regions<'c, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
         self.indices.next_back().map(|index| self.get(index))
@@ -134,7 +236,7 @@ impl<'c, S: AsRef<str> + Clone> DoubleEndedIterator for Regions<'c, S> {
     }
 }
 
-impl<'c, S: AsRef<str> + Clone> ExactSizeIterator for Regions<'c, S> {
+impl<'c, S: AsRef<[u8]> + Clone> ExactSizeIterator for Regions<'c, S> {
     #[inline]
     fn len(&self) -> usize {
         self.indices.len()
@@ -0,0 +1,194 @@
+/// The strategy used to find a copy of each seed in the sequence.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Seeding {
+    /// Search the window following each seed for a copy, like MinCED.
+    #[default]
+    Scan,
+    /// Look up copies of each seed in a k-mer index of the sequence.
+    ///
+    /// The index is built once per sequence, and uses about four bytes
+    /// per base. Seeds are extended exactly as with [`Seeding::Scan`], so
+    /// the same CRISPR regions are found.
+    Index,
+}
+
+/// The maximum number of bits used to address the index buckets.
+const MAX_BUCKET_BITS: usize = 22;
+
+/// Get the 2-bit code of a nucleotide, if it is one of `A`, `C`, `G` or `T`.
+#[inline]
+fn encode(c: u8, ignore_case: bool) -> Option<u64> {
+    let c = if ignore_case {
+        c.to_ascii_uppercase()
+    } else {
+        c
+    };
+    match c {
+        b'A' => Some(0),
+        b'C' => Some(1),
+        b'G' => Some(2),
+        b'T' => Some(3),
+        _ => None,
+    }
+}
+
+/// The link of a k-mer without any later occurrence.
+const NONE: u32 = u32::MAX;
+/// The link of a k-mer that could not be indexed.
+const UNINDEXED: u32 = u32::MAX - 1;
+
+/// An index of the next occurrence of every k-mer of a sequence.
+///
+/// K-mers are 2-bit encoded and hashed into buckets, and each position
+/// links to the next position of a k-mer in the same bucket. Seeds are
+/// mostly looked up in increasing order, so the links are read almost
+/// sequentially. K-mers containing other symbols than `A`, `C`, `G` or
+/// `T` are not indexed, and must be searched in the sequence directly.
+#[derive(Debug, Clone)]
+pub(crate) struct KmerIndex {
+    k: usize,
+    ignore_case: bool,
+    next: Vec<u32>,
+}
+
+impl KmerIndex {
+    /// Build an index of the `k`-mers of a sequence.
+    ///
+    /// Returns `None` if `k` is not between 1 and 32, or if the sequence
+    /// is too long for its positions to be stored in 32 bits.
+    pub fn new(sequence: &[u8], k: usize, ignore_case: bool) -> Option<Self> {
+        if k == 0 || k > 32 || sequence.len() >= UNINDEXED as usize {
+            return None;
+        }
+        let bits = (2 * k).min(MAX_BUCKET_BITS);
+        let mask = if k == 32 {
+            u64::MAX
+        } else {
+            (1 << (2 * k)) - 1
+        };
+        let bucket = |code: u64| {
+            if 2 * k <= bits {
+                code as usize
+            } else {
+                (code.wrapping_mul(0x9E3779B97F4A7C15) >> (64 - bits)) as usize
+            }
+        };
+
+        // scan the sequence backwards, remembering the last k-mer of each bucket
+        let mut codes = [u8::MAX; 256];
+        for c in 0..=255u8 {
+            if let Some(x) = encode(c, ignore_case) {
+                codes[c as usize] = x as u8;
+            }
+        }
+        let mut last = vec![NONE; 1 << bits];
+        let mut next = vec![UNINDEXED; sequence.len()];
+        let mut code = 0u64;
+        let mut valid = 0;
+        for (i, &c) in sequence.iter().enumerate().rev() {
+            let x = codes[c as usize];
+            if x == u8::MAX {
+                valid = 0;
+                continue;
+            }
+            code = (code >> 2) | ((x as u64) << (2 * k - 2));
+            valid += 1;
+            if valid >= k {
+                let b = bucket(code & mask);
+                next[i] = last[b];
+                last[b] = i as u32;
+            }
+        }
+
+        Some(Self {
+            k,
+            ignore_case,
+            next,
+        })
+    }
+
+    /// Find the first copy of the k-mer at `j` contained in `sequence[begin..end]`.
+    ///
+    /// `begin` must be greater than `j`. Returns `None` if the k-mer could
+    /// not be indexed, and `Some(None)` if it has no copy in that range.
+    pub fn find(
+        &self,
+        sequence: &[u8],
+        j: usize,
+        begin: usize,
+        end: usize,
+    ) -> Option<Option<usize>> {
+        let mut q = *self.next.get(j)?;
+        if q == UNINDEXED {
+            return None;
+        }
+        let pattern = &sequence[j..j + self.k];
+        while q != NONE && q as usize + self.k <= end {
+            let p = q as usize;
+            if p >= begin {
+                let kmer = &sequence[p..p + self.k];
+                let matched = if self.ignore_case {
+                    kmer.eq_ignore_ascii_case(pattern)
+                } else {
+                    kmer == pattern
+                };
+                if matched {
+                    return Some(Some(p));
+                }
+            }
+            q = self.next[p];
+        }
+        Some(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Find the first copy of `pattern` in `sequence[begin..end]` naively.
+    fn naive(sequence: &[u8], pattern: &[u8], begin: usize, end: usize) -> Option<usize> {
+        (begin..=end.saturating_sub(pattern.len()))
+            .find(|&p| sequence[p..p + pattern.len()].eq_ignore_ascii_case(pattern))
+    }
+
+    #[test]
+    fn find() {
+        let sequence = b"ACGTTGCAACGTNACGTacgtTTGCAACGT";
+        let index = KmerIndex::new(sequence, 4, false).unwrap();
+        assert_eq!(index.find(sequence, 0, 1, 30), Some(Some(8)));
+        assert_eq!(index.find(sequence, 0, 9, 30), Some(Some(13)));
+        assert_eq!(index.find(sequence, 0, 14, 29), Some(None));
+        assert_eq!(index.find(sequence, 0, 14, 30), Some(Some(26)));
+        assert_eq!(index.find(sequence, 10, 11, 30), None);
+        assert_eq!(index.find(sequence, 17, 18, 30), None);
+        assert_eq!(index.find(sequence, 27, 28, 30), None);
+
+        let index = KmerIndex::new(sequence, 4, true).unwrap();
+        assert_eq!(index.find(sequence, 0, 14, 30), Some(Some(17)));
+        assert_eq!(index.find(sequence, 17, 18, 30), Some(Some(26)));
+
+        assert!(KmerIndex::new(sequence, 0, false).is_none());
+        assert!(KmerIndex::new(sequence, 33, false).is_none());
+    }
+
+    #[test]
+    fn find_hashed() {
+        // k-mers longer than the bucket bits are hashed, check collisions
+        let mut state = 3u32;
+        let sequence = (0..5000)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                b"ACGT"[(state >> 30) as usize]
+            })
+            .collect::<Vec<u8>>();
+        let index = KmerIndex::new(&sequence, 12, true).unwrap();
+        for j in (0..4900).step_by(7) {
+            let pattern = &sequence[j..j + 12];
+            let found = index.find(&sequence, j, j + 1, 5000);
+            assert_eq!(found, Some(naive(&sequence, pattern, j + 1, 5000)));
+        }
+    }
+}
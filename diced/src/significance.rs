@@ -0,0 +1,203 @@
+use super::Crispr;
+use super::ScannerBuilder;
+
+/// The statistical significance of a CRISPR region under a null model.
+///
+/// The null model is built by scanning shuffled copies of the sequence
+/// which preserve its dinucleotide composition, so that the CRISPR
+/// regions found in the shuffles are false positives caused by the
+/// composition of the sequence. The [`Evidence::score`](crate::Evidence::score)
+/// of each region is then compared to the scores of these false positives.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Significance {
+    p_value: f64,
+    e_value: f64,
+}
+
+impl Significance {
+    /// Get the empirical p-value of the CRISPR region.
+    ///
+    /// This is the fraction of shuffled sequences containing a CRISPR
+    /// region scoring at least as high, with a pseudocount so that it is
+    /// never zero.
+    #[inline]
+    pub fn p_value(&self) -> f64 {
+        self.p_value
+    }
+
+    /// Get the E-value of the CRISPR region.
+    ///
+    /// This is the average number of CRISPR regions scoring at least as
+    /// high in a shuffled sequence, or NaN if no shuffle was scanned.
+    #[inline]
+    pub fn e_value(&self) -> f64 {
+        self.e_value
+    }
+}
+
+/// A small pseudo-random number generator (SplitMix64).
+///
+/// The shuffles only need to be reproducible across platforms for a
+/// given seed, not cryptographically secure.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Get a random number in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+}
+
+/// Shuffle a sequence while preserving its dinucleotide counts exactly.
+///
+/// This uses the Altschul-Erickson algorithm: the sequence is seen as an
+/// Eulerian path in the graph of its dinucleotides, and a random Eulerian
+/// path starting and ending with the same symbols is drawn.
+fn dinucleotide_shuffle(sequence: &[u8], rng: &mut Rng) -> Vec<u8> {
+    if sequence.len() < 3 {
+        return sequence.to_vec();
+    }
+
+    let mut edges = vec![Vec::new(); 256];
+    for w in sequence.windows(2) {
+        edges[w[0] as usize].push(w[1]);
+    }
+
+    // draw a random arborescence of last edges towards the last symbol
+    let last = sequence[sequence.len() - 1] as usize;
+    let mut in_tree = [false; 256];
+    let mut next = [0u8; 256];
+    in_tree[last] = true;
+    for v in 0..256 {
+        if edges[v].is_empty() {
+            continue;
+        }
+        let mut u = v;
+        while !in_tree[u] {
+            next[u] = edges[u][rng.below(edges[u].len())];
+            u = next[u] as usize;
+        }
+        let mut u = v;
+        while !in_tree[u] {
+            in_tree[u] = true;
+            u = next[u] as usize;
+        }
+    }
+
+    // shuffle the other edges and keep the last edges at the end
+    for (v, out) in edges.iter_mut().enumerate() {
+        if out.is_empty() {
+            continue;
+        }
+        if v != last {
+            let k = out.iter().position(|&c| c == next[v]).unwrap();
+            let n = out.len() - 1;
+            out.swap(k, n);
+        }
+        let n = if v == last { out.len() } else { out.len() - 1 };
+        for i in (1..n).rev() {
+            out.swap(i, rng.below(i + 1));
+        }
+    }
+
+    let mut cursors = [0usize; 256];
+    let mut shuffled = Vec::with_capacity(sequence.len());
+    let mut c = sequence[0];
+    shuffled.push(c);
+    for _ in 1..sequence.len() {
+        let v = c as usize;
+        c = edges[v][cursors[v]];
+        cursors[v] += 1;
+        shuffled.push(c);
+    }
+    shuffled
+}
+
+/// Compute the significance of CRISPR regions found in a sequence.
+pub(crate) fn significance<T: AsRef<[u8]>>(
+    builder: &ScannerBuilder,
+    sequence: &[u8],
+    crisprs: &[Crispr<T>],
+) -> Vec<Significance> {
+    let mut rng = Rng::new(builder.null_seed);
+    let shuffles = builder.null_shuffles;
+
+    let mut scores = Vec::new();
+    let mut best = Vec::with_capacity(shuffles);
+    for _ in 0..shuffles {
+        let shuffled = dinucleotide_shuffle(sequence, &mut rng);
+        let mut max = f32::NEG_INFINITY;
+        for crispr in builder.scan(shuffled.as_slice()) {
+            let score = crispr.evidence().score();
+            max = max.max(score);
+            scores.push(score);
+        }
+        best.push(max);
+    }
+
+    crisprs
+        .iter()
+        .map(|crispr| {
+            let score = crispr.evidence().score();
+            let hits = best.iter().filter(|&&s| s >= score).count();
+            let total = scores.iter().filter(|&&s| s >= score).count();
+            Significance {
+                p_value: (hits + 1) as f64 / (shuffles + 1) as f64,
+                e_value: if shuffles > 0 {
+                    total as f64 / shuffles as f64
+                } else {
+                    f64::NAN
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dinucleotides(sequence: &[u8]) -> Vec<usize> {
+        let mut counts = vec![0; 256 * 256];
+        for w in sequence.windows(2) {
+            counts[w[0] as usize * 256 + w[1] as usize] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn shuffle() {
+        let sequence = b"ACGTTGCAAGGCTTACGATCGATCGGGATTACAACGTNNACGT";
+        let mut rng = Rng::new(42);
+        let shuffled = dinucleotide_shuffle(sequence, &mut rng);
+        assert_eq!(shuffled.len(), sequence.len());
+        assert_ne!(shuffled.as_slice(), &sequence[..]);
+        assert_eq!(shuffled.first(), sequence.first());
+        assert_eq!(shuffled.last(), sequence.last());
+        assert_eq!(dinucleotides(&shuffled), dinucleotides(sequence));
+
+        let again = dinucleotide_shuffle(sequence, &mut Rng::new(42));
+        assert_eq!(shuffled, again);
+    }
+
+    #[test]
+    fn shuffle_short() {
+        let mut rng = Rng::new(0);
+        assert_eq!(dinucleotide_shuffle(b"", &mut rng), b"");
+        assert_eq!(dinucleotide_shuffle(b"AC", &mut rng), b"AC");
+        assert_eq!(dinucleotide_shuffle(b"AAAA", &mut rng), b"AAAA");
+    }
+}
@@ -0,0 +1,201 @@
+use super::Crispr;
+use super::DnaCount;
+
+/// The length of the flanks used to compare AT content on both sides.
+const FLANK_LENGTH: usize = 100;
+/// The number of bases searched for repeat motifs at each repeat end.
+const MOTIF_WINDOW: usize = 8;
+
+/// The relative weights of the signals used to predict the orientation.
+const MOTIF_WEIGHT: f32 = 0.40;
+const DEGENERACY_WEIGHT: f32 = 0.35;
+const LEADER_WEIGHT: f32 = 0.25;
+
+/// The strand a CRISPR array is transcribed from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Strand {
+    /// The array is transcribed from the scanned sequence.
+    Plus,
+    /// The array is transcribed from the reverse complement of the scanned sequence.
+    Minus,
+    /// The orientation of the array could not be predicted.
+    #[default]
+    Unknown,
+}
+
+impl Strand {
+    /// Get the GFF3 symbol for this strand.
+    pub fn as_char(&self) -> char {
+        match self {
+            Strand::Plus => '+',
+            Strand::Minus => '-',
+            Strand::Unknown => '.',
+        }
+    }
+}
+
+/// Get the complement of a single nucleotide, including IUPAC codes.
+#[inline]
+pub(crate) fn complement(c: u8) -> u8 {
+    match c {
+        b'A' => b'T',
+        b'C' => b'G',
+        b'G' => b'C',
+        b'T' | b'U' => b'A',
+        b'R' => b'Y',
+        b'Y' => b'R',
+        b'K' => b'M',
+        b'M' => b'K',
+        b'B' => b'V',
+        b'V' => b'B',
+        b'D' => b'H',
+        b'H' => b'D',
+        b'a' => b't',
+        b'c' => b'g',
+        b'g' => b'c',
+        b't' | b'u' => b'a',
+        b'r' => b'y',
+        b'y' => b'r',
+        b'k' => b'm',
+        b'm' => b'k',
+        b'b' => b'v',
+        b'v' => b'b',
+        b'd' => b'h',
+        b'h' => b'd',
+        other => other,
+    }
+}
+
+/// Get the reverse complement of a nucleotide sequence.
+pub(crate) fn reverse_complement(sequence: &[u8]) -> Vec<u8> {
+    sequence.iter().rev().map(|&c| complement(c)).collect()
+}
+
+/// Compute the fraction of A and T in a sequence.
+fn at_content(sequence: &[u8]) -> Option<f32> {
+    let mut counts = DnaCount::new();
+    for &c in sequence {
+        counts.count(c);
+    }
+    match counts.total() {
+        0 => None,
+        total => Some((counts.a() + counts.t()) as f32 / total as f32),
+    }
+}
+
+/// Check whether a window ends with a known 3' repeat motif.
+fn has_motif_3p(window: &[u8]) -> bool {
+    window
+        .windows(5)
+        .any(|w| w[..4].eq_ignore_ascii_case(b"GAAA") && matches!(w[4], b'C' | b'G' | b'c' | b'g'))
+}
+
+/// Check whether a window starts with the reverse complement of a 3' motif.
+fn has_motif_5p(window: &[u8]) -> bool {
+    window
+        .windows(5)
+        .any(|w| matches!(w[0], b'C' | b'G' | b'c' | b'g') && w[1..].eq_ignore_ascii_case(b"TTTC"))
+}
+
+/// Score the orientation from known repeat motifs.
+///
+/// Many repeat families end with a `GAAA(C/G)` motif on their 3' end, so
+/// finding this motif at the end of the repeats, or its reverse complement
+/// at their start, gives a hint of the orientation.
+fn motif_score(consensus: &[u8]) -> f32 {
+    let w = MOTIF_WINDOW.min(consensus.len());
+    let plus = has_motif_3p(&consensus[consensus.len() - w..]);
+    let minus = has_motif_5p(&consensus[..w]);
+    match (plus, minus) {
+        (true, false) => 1.0,
+        (false, true) => -1.0,
+        _ => 0.0,
+    }
+}
+
+/// Score the orientation from the degeneracy of the terminal repeats.
+///
+/// The repeat at the 3' end of an array (the trailer end) is often
+/// degenerate, since it is the oldest one and does not get duplicated
+/// during spacer acquisition.
+fn degeneracy_score(consensus: &[u8], first: &[u8], last: &[u8]) -> f32 {
+    let mismatches = |repeat: &[u8]| {
+        consensus
+            .iter()
+            .zip(repeat)
+            .filter(|(x, y)| !x.eq_ignore_ascii_case(y))
+            .count()
+    };
+    match mismatches(last).cmp(&mismatches(first)) {
+        std::cmp::Ordering::Greater => 1.0,
+        std::cmp::Ordering::Less => -1.0,
+        std::cmp::Ordering::Equal => 0.0,
+    }
+}
+
+/// Score the orientation from the AT content of the flanking regions.
+///
+/// The leader sequence upstream of the first repeat is usually AT-rich,
+/// so a flank richer in AT than the other likely contains the leader.
+fn leader_score(left: &[u8], right: &[u8]) -> f32 {
+    match (at_content(left), at_content(right)) {
+        (Some(l), Some(r)) => ((l - r) / 0.2).clamp(-1.0, 1.0),
+        _ => 0.0,
+    }
+}
+
+/// Predict the orientation of a CRISPR array.
+///
+/// Returns the predicted strand and a confidence between 0 and 1.
+pub(crate) fn predict<S: AsRef<[u8]> + Clone>(crispr: &Crispr<S>) -> (Strand, f32) {
+    if crispr.len() < 2 {
+        return (Strand::Unknown, 0.0);
+    }
+
+    let length = crispr.sequence.as_ref().len();
+    let n = crispr.len();
+
+    let consensus = crispr.consensus_repeat();
+    let first = crispr.slice(crispr.indices[0], crispr.ends[0]);
+    let last = crispr.slice(crispr.indices[n - 1], crispr.ends[n - 1]);
+
+    // regions crossing the origin use unwrapped coordinates past the length
+    let start = crispr.indices[0];
+    let end = crispr.ends[n - 1];
+    let max_end = if end > length { 2 * length } else { length };
+    let left = crispr.slice(start.saturating_sub(FLANK_LENGTH), start);
+    let right = crispr.slice(end, (end + FLANK_LENGTH).min(max_end));
+
+    let score = MOTIF_WEIGHT * motif_score(&consensus)
+        + DEGENERACY_WEIGHT * degeneracy_score(&consensus, &first, &last)
+        + LEADER_WEIGHT * leader_score(&left, &right);
+
+    if score > 0.0 {
+        (Strand::Plus, score)
+    } else if score < 0.0 {
+        (Strand::Minus, -score)
+    } else {
+        (Strand::Unknown, 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverse_complement_iupac() {
+        assert_eq!(reverse_complement(b"ACGTN"), b"NACGT");
+        assert_eq!(reverse_complement(b"aaRy"), b"rYtt");
+        assert_eq!(reverse_complement(b""), b"");
+    }
+
+    #[test]
+    fn motifs() {
+        assert_eq!(motif_score(b"GTTTTAACTCCACACGGTACATTAGAAAC"), 1.0);
+        assert_eq!(motif_score(b"GTTTCTAATGTACCGTGTGGAGTTAAAAC"), -1.0);
+        assert_eq!(motif_score(b"ACGTACGTACGTACGTACGT"), 0.0);
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::sync::Arc;
+
+use super::ambiguity;
+use super::Ambiguity;
+use super::Crispr;
+use super::Error;
+use super::Scanner;
+use super::ScannerBuilder;
+
+/// An iterator over the CRISPR regions of a sequence read from a reader.
+///
+/// Created by [`ScannerBuilder::scan_reader`]. The sequence is scanned
+/// in overlapping windows of about three times the maximum array span,
+/// so that memory use does not depend on the length of the sequence.
+pub struct StreamScanner<R> {
+    parameters: ScannerBuilder,
+    reader: R,
+    span: usize,
+    buffer: Vec<u8>,
+    base: usize,
+    j: usize,
+    eof: bool,
+    done: bool,
+    error: Option<Error>,
+    pending: VecDeque<Crispr<Arc<[u8]>>>,
+}
+
+impl<R: BufRead> StreamScanner<R> {
+    pub(crate) fn new(parameters: ScannerBuilder, reader: R) -> Self {
+        let span = parameters.max_array_span.max(
+            parameters.max_repeat_length
+                + parameters.max_spacer_length
+                + parameters.search_window_length,
+        );
+        let error = match parameters.validate() {
+            Err(e) => Some(Error::from(e)),
+            Ok(()) if parameters.circular => Some(Error::CircularStream),
+            Ok(()) => None,
+        };
+        Self {
+            parameters,
+            reader,
+            span,
+            buffer: Vec::new(),
+            base: 0,
+            j: 0,
+            eof: false,
+            done: false,
+            error,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Read sequence until the buffer holds `length` bases or the reader ends.
+    fn fill(&mut self, length: usize) -> Result<(), IoError> {
+        while !self.eof && self.buffer.len() < length {
+            let chunk = match self.reader.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if chunk.is_empty() {
+                self.eof = true;
+                break;
+            }
+            let n = chunk.len();
+            for &c in chunk.iter().filter(|c| !c.is_ascii_whitespace()) {
+                if self.parameters.ambiguity == Ambiguity::Reject && ambiguity::is_ambiguous(c) {
+                    let position = self.base + self.buffer.len();
+                    let error = Error::AmbiguousBase { position, base: c };
+                    return Err(IoError::new(ErrorKind::InvalidData, error));
+                }
+                self.buffer.push(c);
+            }
+            self.reader.consume(n);
+        }
+        Ok(())
+    }
+
+    /// Scan the next window of the sequence.
+    fn step(&mut self) -> Result<(), IoError> {
+        // keep enough sequence before the current position for left flanks
+        let drain = self.j.saturating_sub(self.span + self.base);
+        self.buffer.drain(..drain);
+        self.base += drain;
+        self.fill(self.j - self.base + 2 * self.span)?;
+
+        let mut scanner = Scanner::with_parameters(&self.buffer[..], self.parameters.clone());
+        scanner.j = self.j - self.base;
+        if !self.eof {
+            // seeds too close to the end of the window could yield regions
+            // extending past it, so they are scanned with the next window
+            scanner.search_limit = self.buffer.len() - self.span;
+        }
+        while let Some(crispr) = scanner._next_linear(Scanner::_seed) {
+            self.pending.push_back(to_owned(crispr, self.base));
+        }
+
+        self.j = self.base + scanner.j;
+        self.done = self.eof;
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for StreamScanner<R> {
+    type Item = Result<Crispr<Arc<[u8]>>, IoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.error.take() {
+            self.done = true;
+            return Some(Err(IoError::new(ErrorKind::InvalidInput, error)));
+        }
+        loop {
+            if let Some(crispr) = self.pending.pop_front() {
+                return Some(Ok(crispr));
+            }
+            if self.done {
+                return None;
+            }
+            if let Err(e) = self.step() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Copy a CRISPR region found in a window into its own sequence.
+fn to_owned(crispr: Crispr<&[u8]>, base: usize) -> Crispr<Arc<[u8]>> {
+    let start = crispr.indices[0];
+    let end = crispr.ends[crispr.ends.len() - 1];
+    Crispr {
+        sequence: Arc::from(&crispr.sequence[start..end]),
+        indices: crispr.indices.iter().map(|i| i - start).collect(),
+        ends: crispr.ends.iter().map(|i| i - start).collect(),
+        repeat_length: crispr.repeat_length,
+        strand: crispr.strand,
+        strand_confidence: crispr.strand_confidence,
+        uppercase: crispr.uppercase,
+        offset: base + start,
+    }
+}
@@ -1,15 +1,67 @@
 #![allow(non_snake_case)]
 
-fn test(builder: &diced::ScannerBuilder, gff_path: &str) {
+mod common;
+
+use std::sync::Arc;
+
+/// Read the Aquifex aeolicus VF5 genome.
+fn read_genome() -> noodles_fasta::Record {
     let mut reader = std::fs::File::open("tests/data/Aquifex_aeolicus_VF5.fna")
         .map(std::io::BufReader::new)
         .map(noodles_fasta::Reader::new)
         .unwrap();
-    let record = reader.records().next().unwrap().unwrap();
-    let seq = std::str::from_utf8(record.sequence().as_ref()).unwrap();
+    reader.records().next().unwrap().unwrap()
+}
+
+/// Scan the Aquifex aeolicus VF5 genome with the default parameters.
+fn scan_genome() -> (noodles_fasta::Record, Vec<diced::Crispr<Arc<[u8]>>>) {
+    let record = read_genome();
+    let seq = Arc::<[u8]>::from(record.sequence().as_ref());
+    let crisprs = diced::ScannerBuilder::new().scan(seq).collect();
+    (record, crisprs)
+}
+
+fn test(builder: &diced::ScannerBuilder, gff_path: &str) {
+    let record = read_genome();
+    let seq = record.sequence().as_ref();
 
     let crisprs = builder.scan(seq).collect::<Vec<_>>();
 
+    let index_crisprs = builder
+        .clone()
+        .seeding(diced::Seeding::Index)
+        .scan(seq)
+        .collect::<Vec<_>>();
+    common::assert_same_crisprs(&crisprs, &index_crisprs);
+
+    #[cfg(feature = "rayon")]
+    {
+        let par_crisprs = builder.par_scan(seq).unwrap();
+        common::assert_same_crisprs(&crisprs, &par_crisprs);
+    }
+
+    let stream_crisprs = std::fs::File::open("tests/data/Aquifex_aeolicus_VF5.fna")
+        .map(std::io::BufReader::new)
+        .map(|mut reader| {
+            // skip the FASTA header and stream the sequence lines
+            std::io::BufRead::read_line(&mut reader, &mut String::new()).unwrap();
+            builder.clone().max_array_span(5000).scan_reader(reader)
+        })
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    common::assert_same_crisprs(&crisprs, &stream_crisprs);
+
+    let mut writer = diced::io::gff::GffWriter::new(Vec::new());
+    let seqid = std::str::from_utf8(record.name()).unwrap();
+    for crispr in &crisprs {
+        writer.write(seqid, crispr).unwrap();
+    }
+    assert_eq!(
+        String::from_utf8(writer.into_inner()).unwrap(),
+        std::fs::read_to_string(gff_path).unwrap()
+    );
+
     let gff = std::fs::File::open(gff_path)
         .map(std::io::BufReader::new)
         .map(noodles_gff::Reader::new)
@@ -32,8 +84,8 @@ fn test(builder: &diced::ScannerBuilder, gff_path: &str) {
         );
         let unit_seq = expected_region.attributes().get("rpt_unit_seq").unwrap();
         assert_eq!(
-            actual_region.repeats().nth(1).unwrap().as_str(),
-            unit_seq.as_string().unwrap()
+            actual_region.repeats().nth(1).unwrap().as_bytes(),
+            unit_seq.as_string().unwrap().as_bytes()
         );
     }
 
@@ -77,6 +129,14 @@ fn maxSL30() {
     )
 }
 
+#[test]
+fn searchWL6() {
+    test(
+        diced::ScannerBuilder::new().search_window_length(6),
+        "tests/data/Aquifex_aeolicus_VF5.searchWL6.gff",
+    )
+}
+
 #[test]
 fn minRL30_maxRL40() {
     test(
@@ -111,3 +171,288 @@ fn maxSL34_minSL20_minNR4_minRL20_maxRL40() {
         "tests/data/Aquifex_aeolicus_VF5.maxSL34.minSL20.minNR4.minRL20.maxRL40.gff",
     )
 }
+
+#[test]
+fn gff_spacers() {
+    let (_, crisprs) = scan_genome();
+
+    let mut writer = diced::io::gff::GffWriter::new(Vec::new());
+    writer
+        .source("diced")
+        .id_prefix("array")
+        .repeat_prefix("repeat")
+        .spacers(true);
+    for crispr in &crisprs {
+        writer.write("NC_000918.1", crispr).unwrap();
+    }
+    let gff = String::from_utf8(writer.into_inner()).unwrap();
+    let lines = gff.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "##gff-version 3");
+    assert!(lines[1].starts_with("NC_000918.1\tdiced\trepeat_region\t156460\t156767\t5\t"));
+    assert!(lines[1].contains("ID=array1;"));
+    assert!(lines[2].ends_with("Parent=array1;ID=repeat.array1.1"));
+    assert_eq!(
+        lines[7],
+        "NC_000918.1\tdiced\tspacer\t156490\t156525\t.\t.\t.\tParent=array1;ID=SP.array1.1"
+    );
+    let spacers = lines.iter().filter(|l| l.contains("\tspacer\t")).count();
+    assert_eq!(
+        spacers,
+        crisprs.iter().map(|c| c.spacers().len()).sum::<usize>()
+    );
+}
+
+#[test]
+fn report() {
+    let (record, crisprs) = scan_genome();
+    let seqid = std::str::from_utf8(record.name()).unwrap();
+    let length = record.sequence().len();
+
+    let mut writer = diced::io::report::ReportWriter::new(Vec::new());
+    let elapsed = std::time::Duration::from_millis(0);
+    writer.write(seqid, length, &crisprs, elapsed).unwrap();
+    // sequences without CRISPR regions are skipped, and regions are
+    // numbered across sequences
+    writer.write("empty", 0, &crisprs[..0], elapsed).unwrap();
+    writer.write(seqid, length, &crisprs[..1], elapsed).unwrap();
+    let report = String::from_utf8(writer.into_inner()).unwrap();
+
+    // ranges and repeat positions are checked against the MinCED GFF3 output
+    let gff = std::fs::File::open("tests/data/Aquifex_aeolicus_VF5.gff")
+        .map(std::io::BufReader::new)
+        .map(noodles_gff::Reader::new)
+        .unwrap()
+        .records()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+    let (first, second) = report
+        .split_once("\n\n\n")
+        .map(|(first, second)| (first.lines().collect::<Vec<_>>(), second))
+        .unwrap();
+    assert_eq!(first[0], format!("Sequence '{}' ({} bp)", seqid, length));
+    assert_eq!(first[first.len() - 1], "Time to find repeats: 0 ms");
+
+    let ranges = first
+        .iter()
+        .filter(|line| line.starts_with("CRISPR "))
+        .cloned()
+        .collect::<Vec<_>>();
+    let repeat_regions = gff
+        .iter()
+        .filter(|record| record.ty() == "repeat_region")
+        .collect::<Vec<_>>();
+    assert_eq!(ranges.len(), repeat_regions.len());
+    for (i, (range, region)) in ranges.iter().zip(&repeat_regions).enumerate() {
+        assert_eq!(
+            *range,
+            format!(
+                "CRISPR {}   Range: {} - {}",
+                i + 1,
+                region.start().get(),
+                region.end().get()
+            )
+        );
+    }
+
+    let repeats = first
+        .iter()
+        .filter_map(|line| {
+            let mut fields = line.split("\t\t");
+            let position = fields.next()?.parse::<usize>().ok()?;
+            let sequence = fields.next()?.split('\t').next()?;
+            Some((position, sequence))
+        })
+        .collect::<Vec<_>>();
+    let repeat_units = gff
+        .iter()
+        .filter(|record| record.ty() == "repeat_unit")
+        .collect::<Vec<_>>();
+    assert_eq!(repeats.len(), repeat_units.len());
+    for (((position, sequence), unit), repeat) in repeats
+        .iter()
+        .zip(&repeat_units)
+        .zip(crisprs.iter().flat_map(|c| c.repeats()))
+    {
+        assert_eq!(*position, unit.start().get());
+        assert_eq!(sequence.as_bytes(), repeat.as_bytes());
+    }
+
+    assert!(second.starts_with(&format!("Sequence '{}'", seqid)));
+    assert!(second.contains(&format!("\nCRISPR {}   Range: ", crisprs.len() + 1)));
+    assert!(!second.contains("'empty'"));
+}
+
+#[test]
+fn report_layout() {
+    let (record, crisprs) = scan_genome();
+    let length = record.sequence().len();
+
+    let mut writer = diced::io::report::ReportWriter::new(Vec::new());
+    let elapsed = std::time::Duration::from_millis(12);
+    writer
+        .write("NC_000918.1", length, &crisprs[..1], elapsed)
+        .unwrap();
+    let report = String::from_utf8(writer.into_inner()).unwrap();
+
+    // the last repeat line keeps the tab separating the spacer column
+    let expected = concat!(
+        "Sequence 'NC_000918.1' (1551335 bp)\n",
+        "\n",
+        "CRISPR 1   Range: 156460 - 156767\n",
+        "POSITION\tREPEAT\t\t\t\tSPACER\n",
+        "--------\t------------------------------\t---------------------------------------\n",
+        "156460\t\tGTTCCTAATGTACCGTGTGGAGTTGAAACC\tCAGTCAGATTGAAGTTATCGTCAACTTCAAAATACG\t[ 30, 36 ]\n",
+        "156526\t\tGTTCCTAATGTACCGTGTGGAGTTGAAACC\tTTGCTTTATCAGAGAATAGTATTCTTTCTATGTCTTCATAGTTT\t[ 30, 44 ]\n",
+        "156600\t\tGTTCCTAATGTACCGTGTGGAGTTGAAACC\tTCTTGATAGTATCTGTTTTCCGCGTATTCCACGATTCTTTT\t[ 30, 41 ]\n",
+        "156671\t\tGTTCCTAATGTACCGTAGTGGAGTTGAAAC\tTTCGGTGATAAAGGAGCATTTTTCACAAAAGAAATCA\t[ 30, 37 ]\n",
+        "156738\t\tGTTTCTAATGTACCGTGTGGATAAAAATGA\t\n",
+        "--------\t------------------------------\t---------------------------------------\n",
+        "Repeats: 5\tAverage Length: 30\t\tAverage Length: 39\n",
+        "\n",
+        "Time to find repeats: 12 ms\n",
+        "\n",
+        "\n",
+    );
+    assert_eq!(report, expected);
+}
+
+#[test]
+fn fasta() {
+    let (_, crisprs) = scan_genome();
+
+    let mut writer = diced::io::fasta::FastaWriter::new(Vec::new());
+    for crispr in &crisprs {
+        writer.write("NC_000918.1", crispr).unwrap();
+    }
+    let fasta = writer.into_inner();
+    let records = noodles_fasta::Reader::new(fasta.as_slice())
+        .records()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let spacers = crisprs.iter().flat_map(|c| c.spacers()).collect::<Vec<_>>();
+    assert_eq!(records.len(), spacers.len());
+    for (record, spacer) in records.iter().zip(&spacers) {
+        assert_eq!(record.sequence().as_ref(), &spacer.to_bytes()[..]);
+    }
+    assert_eq!(records[0].name(), b"NC_000918.1_CRISPR_1_spacer_1");
+    assert_eq!(
+        records[0].description(),
+        Some(&b"NC_000918.1:156490-156525"[..])
+    );
+    assert_eq!(records[4].name(), b"NC_000918.1_CRISPR_2_spacer_1");
+    assert_eq!(
+        records[1].description(),
+        Some(&b"NC_000918.1:156556-156599"[..])
+    );
+
+    let mut writer = diced::io::fasta::FastaWriter::new(Vec::new());
+    writer.repeats(true);
+    writer.write("NC_000918.1", &crisprs[0]).unwrap();
+    let fasta = String::from_utf8(writer.into_inner()).unwrap();
+    let names = fasta
+        .lines()
+        .filter_map(|l| l.strip_prefix('>'))
+        .map(|l| l.split(' ').next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names.len(), 9);
+    assert_eq!(names[0], "NC_000918.1_CRISPR_1_repeat_1");
+    assert_eq!(names[1], "NC_000918.1_CRISPR_1_spacer_1");
+    assert_eq!(names[8], "NC_000918.1_CRISPR_1_repeat_5");
+}
+
+#[test]
+fn bed() {
+    let (_, crisprs) = scan_genome();
+
+    let mut writer = diced::io::bed::BedWriter::new(Vec::new());
+    for crispr in &crisprs {
+        writer.write("NC_000918.1", crispr).unwrap();
+    }
+    let bed = String::from_utf8(writer.into_inner()).unwrap();
+    let lines = bed.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), crisprs.len());
+    assert_eq!(
+        lines[0],
+        format!(
+            "NC_000918.1\t156459\t156767\tCRISPR1\t5\t{}",
+            crisprs[0].strand().as_char()
+        )
+    );
+
+    let mut writer = diced::io::bed::BedWriter::new(Vec::new());
+    writer.id_prefix("array").bed12(true);
+    for crispr in &crisprs {
+        writer.write("NC_000918.1", crispr).unwrap();
+    }
+    let bed = String::from_utf8(writer.into_inner()).unwrap();
+    for (line, crispr) in bed.lines().zip(&crisprs) {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        assert_eq!(fields.len(), 12);
+        assert_eq!(fields[6], fields[1]);
+        assert_eq!(fields[7], fields[2]);
+        assert_eq!(fields[9], crispr.len().to_string());
+        let sizes = fields[10].split(',').collect::<Vec<_>>();
+        let starts = fields[11].split(',').collect::<Vec<_>>();
+        assert_eq!(sizes.len(), crispr.len());
+        assert_eq!(starts[0], "0");
+        let start = fields[1].parse::<usize>().unwrap();
+        let end = fields[2].parse::<usize>().unwrap();
+        let last_start = starts[crispr.len() - 1].parse::<usize>().unwrap();
+        let last_size = sizes[crispr.len() - 1].parse::<usize>().unwrap();
+        assert_eq!(start + last_start + last_size, end);
+    }
+    assert!(bed.starts_with("NC_000918.1\t156459\t156767\tarray1\t5\t"));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn json() {
+    let (_, crisprs) = scan_genome();
+
+    let mut writer = diced::io::json::JsonWriter::new(Vec::new());
+    for crispr in &crisprs {
+        writer.write("NC_000918.1", crispr).unwrap();
+    }
+    let json = String::from_utf8(writer.into_inner()).unwrap();
+    let records = json
+        .lines()
+        .map(serde_json::from_str::<diced::io::json::CrisprRecord>)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(records.len(), crisprs.len());
+    for (i, (record, crispr)) in records.iter().zip(&crisprs).enumerate() {
+        assert_eq!(record.seqid, "NC_000918.1");
+        assert_eq!(record.id, format!("CRISPR{}", i + 1));
+        assert_eq!(record.start, crispr.start());
+        assert_eq!(record.end, crispr.end());
+        assert_eq!(record.strand, crispr.strand());
+        assert_eq!(record.repeats.len(), crispr.len());
+        assert_eq!(record.spacers.len(), crispr.spacers().len());
+        for (element, spacer) in record.spacers.iter().zip(crispr.spacers()) {
+            assert_eq!(element.start, spacer.start());
+            assert_eq!(element.end, spacer.end());
+            assert_eq!(element.sequence.as_bytes(), &spacer.to_bytes()[..]);
+        }
+        assert_eq!(
+            record,
+            &diced::io::json::CrisprRecord::new("NC_000918.1", &record.id, crispr)
+        );
+    }
+
+    let value = serde_json::from_str::<serde_json::Value>(json.lines().next().unwrap()).unwrap();
+    assert_eq!(value["start"], 156459);
+    assert_eq!(value["end"], 156767);
+    assert_eq!(value["spacers"][0]["start"], 156489);
+
+    let mut builder = diced::ScannerBuilder::new();
+    builder.min_repeat_count(4).seeding(diced::Seeding::Index);
+    let parameters = serde_json::to_value(&builder).unwrap();
+    assert_eq!(parameters["min_repeat_count"], 4);
+    assert_eq!(parameters["seeding"], "index");
+    assert_eq!(parameters["ambiguity"], "ignore");
+    let builder = serde_json::from_str::<diced::ScannerBuilder>(
+        r#"{"min_repeat_count": 4, "seeding": "index"}"#,
+    )
+    .unwrap();
+    assert_eq!(serde_json::to_value(&builder).unwrap(), parameters);
+}
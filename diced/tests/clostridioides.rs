@@ -1,4 +1,7 @@
 #![allow(non_snake_case)]
+#![allow(clippy::needless_borrow)]
+
+mod common;
 
 fn test(builder: &diced::ScannerBuilder, gff_path: &str) {
     let mut reader = std::fs::File::open("tests/data/NZ_CP019870.1.fna")
@@ -6,10 +9,33 @@ fn test(builder: &diced::ScannerBuilder, gff_path: &str) {
         .map(noodles_fasta::Reader::new)
         .unwrap();
     let record = reader.records().next().unwrap().unwrap();
-    let seq = std::str::from_utf8(record.sequence().as_ref()).unwrap();
+    let seq = record.sequence().as_ref();
 
     let crisprs = builder.scan(seq).collect::<Vec<_>>();
 
+    let index_crisprs = builder
+        .clone()
+        .seeding(diced::Seeding::Index)
+        .scan(seq)
+        .collect::<Vec<_>>();
+    common::assert_same_crisprs(&crisprs, &index_crisprs);
+
+    #[cfg(feature = "rayon")]
+    {
+        let par_crisprs = builder.par_scan(seq).unwrap();
+        common::assert_same_crisprs(&crisprs, &par_crisprs);
+    }
+
+    let mut writer = diced::io::gff::GffWriter::new(Vec::new());
+    let seqid = std::str::from_utf8(record.name()).unwrap();
+    for crispr in &crisprs {
+        writer.write(seqid, crispr).unwrap();
+    }
+    assert_eq!(
+        String::from_utf8(writer.into_inner()).unwrap(),
+        std::fs::read_to_string(gff_path).unwrap()
+    );
+
     let gff = std::fs::File::open(gff_path)
         .map(std::io::BufReader::new)
         .map(noodles_gff::Reader::new)
@@ -32,8 +58,8 @@ fn test(builder: &diced::ScannerBuilder, gff_path: &str) {
         );
         let unit_seq = expected_region.attributes().get("rpt_unit_seq").unwrap();
         assert_eq!(
-            actual_region.repeats().nth(1).unwrap().as_str(),
-            unit_seq.as_string().unwrap()
+            actual_region.repeats().nth(1).unwrap().as_bytes(),
+            unit_seq.as_string().unwrap().as_bytes()
         );
     }
 
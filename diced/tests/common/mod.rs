@@ -0,0 +1,19 @@
+//! Helpers shared by the integration tests.
+
+/// Check that two scans found the same CRISPR regions.
+pub fn assert_same_crisprs<S, T>(expected: &[diced::Crispr<S>], actual: &[diced::Crispr<T>])
+where
+    S: AsRef<[u8]> + Clone,
+    T: AsRef<[u8]> + Clone,
+{
+    assert_eq!(expected.len(), actual.len());
+    for (expected_crispr, actual_crispr) in expected.iter().zip(actual) {
+        assert_eq!(expected_crispr.len(), actual_crispr.len());
+        for (expected_repeat, actual_repeat) in
+            expected_crispr.repeats().zip(actual_crispr.repeats())
+        {
+            assert_eq!(expected_repeat.start(), actual_repeat.start());
+            assert_eq!(expected_repeat.end(), actual_repeat.end());
+        }
+    }
+}